@@ -0,0 +1,65 @@
+//! Measures tokenizer and parser throughput on a generated multi-megabyte schema dump.
+//!
+//! Run with `cargo bench`. The `owned tokens` line converts every token with `Token::into_owned`, which allocates a `String` for every identifier, literal and comment the way the tokenizer did before it borrowed from the input, so the two tokenizer lines show what borrowing saves.
+
+use std::hint::black_box;
+use std::time::{Duration, Instant};
+
+use angunna_gamage_thilina_ranmal::{GenericDialect, Parser, StatementReader, Tokenizer};
+
+const TABLES: usize = 20_000;
+const ROUNDS: u32 = 5;
+
+fn schema_dump() -> String {
+    let mut sql = String::new();
+    for i in 0..TABLES {
+        sql.push_str(&format!(
+            "-- table {i}\nCREATE TABLE table_{i} (\n    id INT PRIMARY KEY,\n    name VARCHAR(255) NOT NULL,\n    \"Display Name\" VARCHAR(64),\n    is_active BOOL,\n    age INT CHECK (age >= 18 AND age <= 65)\n);\n"
+        ));
+        sql.push_str(&format!(
+            "SELECT id, name, age * 2 FROM table_{i} WHERE name = 'O''Brien' OR price > 9.99 ORDER BY age DESC, id;\n"
+        ));
+    }
+    sql
+}
+
+fn measure(name: &str, bytes: usize, mut run: impl FnMut() -> usize) {
+    let mut best = Duration::MAX;
+    let mut items = 0;
+    for _ in 0..ROUNDS {
+        let start = Instant::now();
+        items = black_box(run());
+        best = best.min(start.elapsed());
+    }
+    let megabytes = bytes as f64 / (1024.0 * 1024.0);
+    println!(
+        "{:<14} {:>9} items  {:>8.2} ms  {:>7.1} MiB/s",
+        name,
+        items,
+        best.as_secs_f64() * 1000.0,
+        megabytes / best.as_secs_f64()
+    );
+}
+
+fn main() {
+    let sql = schema_dump();
+    println!("input: {:.1} MiB, best of {} rounds", sql.len() as f64 / (1024.0 * 1024.0), ROUNDS);
+
+    measure("tokens", sql.len(), || {
+        Tokenizer::new(&sql, &GenericDialect).inspect(|token| {
+            black_box(token.as_ref().unwrap());
+        }).count()
+    });
+    measure("owned tokens", sql.len(), || {
+        Tokenizer::new(&sql, &GenericDialect).fold(0, |count, token| {
+            black_box(token.unwrap().0.into_owned());
+            count + 1
+        })
+    });
+    measure("statements", sql.len(), || {
+        Parser::new(&sql, &GenericDialect).parse_statements().unwrap().len()
+    });
+    measure("streamed", sql.len(), || {
+        StatementReader::new(sql.as_bytes(), &GenericDialect).map(Result::unwrap).count()
+    });
+}
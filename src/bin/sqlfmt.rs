@@ -0,0 +1,110 @@
+use std::env;
+use std::fs;
+use std::io::{self, Read, Write};
+use std::process;
+
+use angunna_gamage_thilina_ranmal::dialect::dialect_from_name;
+use angunna_gamage_thilina_ranmal::{format_sql, Diagnostic, Dialect, FormatOptions, GenericDialect, KeywordCase};
+
+const USAGE: &str = "Usage: sqlfmt [--check] [--dialect NAME] [--keyword-case upper|lower] [--indent N] [--max-width N] [FILE...]
+
+Formats the given SQL files in place, or standard input to standard output if no file is given.
+With --check, nothing is written and the exit code is 1 if any input is not formatted.";
+
+fn main() -> io::Result<()> {
+    let mut dialect: Box<dyn Dialect> = Box::new(GenericDialect);
+    let mut options = FormatOptions::default();
+    let mut check = false;
+    let mut paths = Vec::new();
+
+    let mut args = env::args().skip(1);
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "--check" => check = true,
+            "--dialect" => {
+                let name = args.next().unwrap_or_default();
+                dialect = dialect_from_name(&name).unwrap_or_else(|| {
+                    usage_error(&format!("Unknown dialect '{}', expected generic, postgres, mysql or sqlite", name))
+                });
+            },
+            "--keyword-case" => {
+                options.keyword_case = match args.next().as_deref() {
+                    Some("upper") => KeywordCase::Upper,
+                    Some("lower") => KeywordCase::Lower,
+                    _ => usage_error("--keyword-case expects upper or lower"),
+                };
+            },
+            "--indent" => options.indent = number_argument(args.next(), "--indent"),
+            "--max-width" => options.max_width = number_argument(args.next(), "--max-width"),
+            "--help" | "-h" => {
+                println!("{}", USAGE);
+                return Ok(());
+            },
+            flag if flag.starts_with("--") => usage_error(&format!("Unknown option '{}'", flag)),
+            _ => paths.push(arg),
+        }
+    }
+
+    let mut failed = false;
+    if paths.is_empty() {
+        let mut source = String::new();
+        io::stdin().read_to_string(&mut source)?;
+        match format_source(&source, "<stdin>", dialect.as_ref(), &options) {
+            Some(formatted) if check => failed = formatted != source,
+            Some(formatted) => io::stdout().write_all(formatted.as_bytes())?,
+            None => failed = true,
+        }
+    }
+
+    for path in &paths {
+        let source = match fs::read_to_string(path) {
+            Ok(source) => source,
+            Err(error) => {
+                eprintln!("sqlfmt: {}: {}", path, error);
+                failed = true;
+                continue;
+            },
+        };
+        match format_source(&source, path, dialect.as_ref(), &options) {
+            Some(formatted) if formatted == source => {},
+            Some(_) if check => {
+                println!("Would reformat {}", path);
+                failed = true;
+            },
+            Some(formatted) => {
+                if let Err(error) = fs::write(path, formatted) {
+                    eprintln!("sqlfmt: {}: {}", path, error);
+                    failed = true;
+                }
+            },
+            None => failed = true,
+        }
+    }
+
+    if failed {
+        process::exit(1);
+    }
+    Ok(())
+}
+
+/// Formats `source`, printing a diagnostic for every parse error. Returns `None` if the source does not parse.
+fn format_source(source: &str, origin: &str, dialect: &dyn Dialect, options: &FormatOptions) -> Option<String> {
+    match format_sql(source, dialect, options) {
+        Ok(formatted) => Some(formatted),
+        Err(errors) => {
+            for error in &errors {
+                eprintln!("{}\n", Diagnostic::new(error, source).with_origin(origin));
+            }
+            None
+        },
+    }
+}
+
+fn number_argument(value: Option<String>, flag: &str) -> usize {
+    value.and_then(|value| value.parse().ok()).unwrap_or_else(|| usage_error(&format!("{} expects a number", flag)))
+}
+
+fn usage_error(message: &str) -> ! {
+    eprintln!("{}\n\n{}", message, USAGE);
+    process::exit(2);
+}
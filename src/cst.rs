@@ -0,0 +1,373 @@
+use std::borrow::Cow;
+use std::fmt::{Display, Formatter};
+
+use crate::dialect::Dialect;
+use crate::error::ParseError;
+use crate::parser::Parser;
+use crate::span::Span;
+use crate::statement::{Constraint, Expression, Statement};
+use crate::token::Token;
+use crate::tokenizer::{Tokenizer, TokenizerOptions};
+
+/// A lossless concrete syntax tree: every byte of the input, including whitespace, comments and text that failed to parse, is stored in a token of the tree, so `to_string()` reproduces the input exactly.
+///
+/// The typed AST is a view over the tree: `statements()` are the statements parsed from the same input, and every AST node's span names the `SyntaxNode` (`node`) or the `SyntaxToken` (`token_mut`) it was read from. A refactoring tool finds what to change through the AST and edits the tokens of the tree, which keeps the author's formatting and comments everywhere else:
+///
+/// ```
+/// use angunna_gamage_thilina_ranmal::{GenericDialect, Statement, SyntaxTree};
+///
+/// let mut tree = SyntaxTree::parse("SELECT  name -- the name\nFROM users;", &GenericDialect);
+/// let Statement::Select { columns, .. } = &tree.statements()[0] else { unreachable!() };
+/// let span = columns[0].span();
+/// tree.token_mut(span.start).unwrap().set_text("full_name");
+/// assert_eq!(tree.to_string(), "SELECT  full_name -- the name\nFROM users;");
+/// ```
+///
+/// Edits only change the text; `statements()` keeps describing the original input, so parse `to_string()` again for an AST of the edited text.
+#[derive(Debug)]
+pub struct SyntaxTree<'a> {
+    root: SyntaxNode<'a>,
+    statements: Vec<Statement<'a>>,
+    errors: Vec<ParseError>,
+}
+
+/// The kind of a `SyntaxNode`, named after the AST node it corresponds to. Identifiers, literals and operators are tokens rather than nodes.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SyntaxKind {
+    /// The root, holding the statements along with the whitespace, comments and unparsable text between them.
+    File,
+    Statement,
+    ColumnDefinition,
+    Constraint,
+    Expression,
+}
+
+/// An inner node of a `SyntaxTree`. Its span covers its first to its last non-trivia token; whitespace and comments around the node belong to its parent.
+#[derive(Debug, Clone)]
+pub struct SyntaxNode<'a> {
+    kind: SyntaxKind,
+    span: Span,
+    children: Vec<SyntaxElement<'a>>,
+}
+
+#[derive(Debug, Clone)]
+pub enum SyntaxElement<'a> {
+    Node(SyntaxNode<'a>),
+    Token(SyntaxToken<'a>),
+}
+
+/// A leaf of a `SyntaxTree`: a token together with the exact text it was read from. `token` is `None` for text the tokenizer rejected, such as an invalid character or an unterminated string.
+#[derive(Debug, Clone)]
+pub struct SyntaxToken<'a> {
+    token: Option<Token<'a>>,
+    text: Cow<'a, str>,
+    span: Span,
+}
+
+impl<'a> SyntaxTree<'a> {
+    /// Parses `input` written in `dialect` into a lossless tree. Parsing never fails: statements with errors are left out of `statements()` and reported in `errors()`, while their text stays in the tree.
+    pub fn parse(input: &'a str, dialect: &'a dyn Dialect) -> Self {
+        let options = TokenizerOptions {
+            emit_comments: true,
+            emit_whitespace: true,
+            ..Default::default()
+        };
+        let tokens = Tokenizer::with_options(input, dialect, options).map(|result| match result {
+            Ok((token, span)) => SyntaxToken {
+                token: Some(token),
+                text: Cow::Borrowed(&input[span.range()]),
+                span,
+            },
+            Err(error) => SyntaxToken {
+                token: None,
+                text: Cow::Borrowed(&input[error.span().range()]),
+                span: error.span(),
+            },
+        });
+
+        let (statements, errors) = Parser::new(input, dialect).parse_statements_with_recovery();
+        let mut ranges = Vec::new();
+        for statement in &statements {
+            statement_ranges(statement, &mut ranges);
+        }
+        let root = build(tokens, ranges, Span::new(0, input.len(), 1, 1));
+
+        Self {
+            root,
+            statements,
+            errors,
+        }
+    }
+
+    pub fn root(&self) -> &SyntaxNode<'a> {
+        &self.root
+    }
+
+    pub fn statements(&self) -> &[Statement<'a>] {
+        &self.statements
+    }
+
+    pub fn errors(&self) -> &[ParseError] {
+        &self.errors
+    }
+
+    /// Returns the innermost node that covers exactly `span`, typically the span of an AST node. A statement without any surrounding whitespace has the same span as the root; the statement node is returned.
+    pub fn node(&self, span: Span) -> Option<&SyntaxNode<'a>> {
+        self.root.node(span)
+    }
+
+    /// Returns the token that starts at byte `offset` of the original input, for editing.
+    pub fn token_mut(&mut self, offset: usize) -> Option<&mut SyntaxToken<'a>> {
+        self.root.token_mut(offset)
+    }
+}
+
+impl<'a> SyntaxNode<'a> {
+    pub fn kind(&self) -> SyntaxKind {
+        self.kind
+    }
+
+    /// The part of the original input the node was read from.
+    pub fn span(&self) -> Span {
+        self.span
+    }
+
+    pub fn children(&self) -> &[SyntaxElement<'a>] {
+        &self.children
+    }
+
+    pub fn children_mut(&mut self) -> &mut Vec<SyntaxElement<'a>> {
+        &mut self.children
+    }
+
+    /// Returns every token below this node, in input order.
+    pub fn tokens(&self) -> Vec<&SyntaxToken<'a>> {
+        let mut tokens = Vec::new();
+        self.collect_tokens(&mut tokens);
+        tokens
+    }
+
+    fn collect_tokens<'t>(&'t self, tokens: &mut Vec<&'t SyntaxToken<'a>>) {
+        for child in &self.children {
+            match child {
+                SyntaxElement::Node(node) => node.collect_tokens(tokens),
+                SyntaxElement::Token(token) => tokens.push(token),
+            }
+        }
+    }
+
+    fn contains(&self, offset: usize) -> bool {
+        self.span.start <= offset && offset < self.span.end
+    }
+
+    fn node(&self, span: Span) -> Option<&SyntaxNode<'a>> {
+        let child = self.children.iter().find_map(|child| match child {
+            SyntaxElement::Node(node) if node.contains(span.start) => node.node(span),
+            _ => None,
+        });
+        child.or_else(|| (self.span.range() == span.range()).then_some(self))
+    }
+
+    fn token_mut(&mut self, offset: usize) -> Option<&mut SyntaxToken<'a>> {
+        self.children.iter_mut().find_map(|child| match child {
+            SyntaxElement::Node(node) if node.contains(offset) => node.token_mut(offset),
+            SyntaxElement::Token(token) if token.span.start == offset => Some(token),
+            _ => None,
+        })
+    }
+}
+
+impl<'a> SyntaxToken<'a> {
+    /// The token as read from the input, or `None` for text the tokenizer rejected. Not updated by `set_text`.
+    pub fn token(&self) -> Option<&Token<'a>> {
+        self.token.as_ref()
+    }
+
+    pub fn text(&self) -> &str {
+        &self.text
+    }
+
+    /// The part of the original input the token was read from.
+    pub fn span(&self) -> Span {
+        self.span
+    }
+
+    /// Returns `true` for whitespace and comments.
+    pub fn is_trivia(&self) -> bool {
+        matches!(self.token, Some(Token::Whitespace(_) | Token::Comment(_)))
+    }
+
+    /// Replaces the text the token is written back as.
+    pub fn set_text(&mut self, text: impl Into<Cow<'a, str>>) {
+        self.text = text.into();
+    }
+}
+
+/// Records the span of `statement` and of every node inside it, parents before children.
+fn statement_ranges(statement: &Statement<'_>, ranges: &mut Vec<(SyntaxKind, Span)>) {
+    ranges.push((SyntaxKind::Statement, statement.span()));
+    match statement {
+        Statement::Select { columns, r#where, orderby, .. } => {
+            for expression in columns.iter().chain(r#where).chain(orderby) {
+                expression_ranges(expression, ranges);
+            }
+        },
+        Statement::CreateTable { column_list, .. } => {
+            for column in column_list {
+                ranges.push((SyntaxKind::ColumnDefinition, column.span));
+                for constraint in &column.constraints {
+                    ranges.push((SyntaxKind::Constraint, constraint.span()));
+                    if let Constraint::Check(expression, _) = constraint {
+                        expression_ranges(expression, ranges);
+                    }
+                }
+            }
+        },
+    }
+}
+
+fn expression_ranges(expression: &Expression<'_>, ranges: &mut Vec<(SyntaxKind, Span)>) {
+    ranges.push((SyntaxKind::Expression, expression.span()));
+    match expression {
+        Expression::BinaryOperation { left_operand, right_operand, .. } => {
+            expression_ranges(left_operand, ranges);
+            expression_ranges(right_operand, ranges);
+        },
+        Expression::UnaryOperation { operand: expression, .. } | Expression::Cast { expression, .. } => {
+            expression_ranges(expression, ranges);
+        },
+        _ => {},
+    }
+}
+
+/// Nests `tokens` into nodes for `ranges`, which are ordered parents first and properly nested, as AST spans are. A node is opened at its first non-trivia token and closed by the first token past its end, so leading and trailing trivia stay with the parent.
+fn build<'a>(tokens: impl Iterator<Item = SyntaxToken<'a>>, mut ranges: Vec<(SyntaxKind, Span)>, span: Span) -> SyntaxNode<'a> {
+    ranges.sort_by_key(|(_, span)| span.start);
+    let mut ranges = ranges.into_iter().peekable();
+    let mut stack = vec![SyntaxNode { kind: SyntaxKind::File, span, children: Vec::new() }];
+
+    for token in tokens {
+        while stack.len() > 1 && stack.last().is_some_and(|node| node.span.end <= token.span.start) {
+            close(&mut stack);
+        }
+        if !token.is_trivia() {
+            while let Some((kind, span)) = ranges.next_if(|(_, span)| span.start <= token.span.start) {
+                if span.end > token.span.start {
+                    stack.push(SyntaxNode { kind, span, children: Vec::new() });
+                }
+            }
+        }
+        if let Some(node) = stack.last_mut() {
+            node.children.push(SyntaxElement::Token(token));
+        }
+    }
+
+    while stack.len() > 1 {
+        close(&mut stack);
+    }
+    stack.pop().expect("the root is never closed")
+}
+
+fn close(stack: &mut Vec<SyntaxNode<'_>>) {
+    if let (Some(node), Some(parent)) = (stack.pop(), stack.last_mut()) {
+        parent.children.push(SyntaxElement::Node(node));
+    }
+}
+
+impl Display for SyntaxTree<'_> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.root)
+    }
+}
+
+impl Display for SyntaxNode<'_> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        for child in &self.children {
+            match child {
+                SyntaxElement::Node(node) => write!(f, "{}", node)?,
+                SyntaxElement::Token(token) => write!(f, "{}", token.text)?,
+            }
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::dialect::{GenericDialect, MySqlDialect};
+
+    #[test]
+    fn test_round_trip() {
+        let inputs = [
+            "",
+            "  \n\t",
+            "SELECT name, surname FROM users;",
+            "-- header\nCREATE TABLE t (\n    id   INT PRIMARY KEY, -- key\n    age INT CHECK ( age >= 18 )\n);\n\n/* trailing /* nested */ */",
+            "SELECT ąžuolas FROM äpfel WHERE x = 'O''Brien' ORDER BY x DESC ;;",
+            "SELECT FROM users; SELECT 'unterminated",
+            "SELECT a ! b FROM t; SELECT price::INT FROM t",
+        ];
+        for input in inputs {
+            let tree = SyntaxTree::parse(input, &GenericDialect);
+            assert_eq!(tree.to_string(), input);
+        }
+
+        let input = "SELECT `a b` FROM t WHERE c = \"x\\\"y\";";
+        assert_eq!(SyntaxTree::parse(input, &MySqlDialect).to_string(), input);
+    }
+
+    #[test]
+    fn test_structure() {
+        let input = "-- c\nSELECT a + 1 AS_x FROM t;  CREATE TABLE t (id INT NOT NULL);";
+        let tree = SyntaxTree::parse(input, &GenericDialect);
+        assert_eq!(tree.statements().len(), 1);
+        assert_eq!(tree.errors().len(), 1);
+
+        let kinds: Vec<SyntaxKind> = tree.root().children().iter().filter_map(|child| match child {
+            SyntaxElement::Node(node) => Some(node.kind()),
+            SyntaxElement::Token(_) => None,
+        }).collect();
+        assert_eq!(kinds, vec![SyntaxKind::Statement]);
+
+        let Statement::CreateTable { column_list, .. } = &tree.statements()[0] else {
+            panic!("expected a CREATE TABLE statement");
+        };
+        let column = tree.node(column_list[0].span).unwrap();
+        assert_eq!(column.kind(), SyntaxKind::ColumnDefinition);
+        assert_eq!(column.to_string(), "id INT NOT NULL");
+        let constraint = tree.node(column_list[0].constraints[0].span()).unwrap();
+        assert_eq!((constraint.kind(), constraint.to_string()), (SyntaxKind::Constraint, "NOT NULL".to_string()));
+    }
+
+    #[test]
+    fn test_trivia_placement() {
+        let tree = SyntaxTree::parse("SELECT /* x */ a , b /* y */ FROM t ;", &GenericDialect);
+        let Statement::Select { columns, .. } = &tree.statements()[0] else {
+            panic!("expected a SELECT statement");
+        };
+        let statement = tree.node(tree.statements()[0].span()).unwrap();
+        assert_eq!(statement.kind(), SyntaxKind::Statement);
+        assert_eq!(statement.tokens().len(), 17);
+        for (column, text) in columns.iter().zip(["a", "b"]) {
+            assert_eq!(tree.node(column.span()).unwrap().to_string(), text);
+        }
+    }
+
+    #[test]
+    fn test_edit() {
+        let input = "CREATE TABLE t (\n  age INT CHECK (age >= 18) -- adults\n);";
+        let mut tree = SyntaxTree::parse(input, &GenericDialect);
+        let Statement::CreateTable { column_list, .. } = &tree.statements()[0] else {
+            panic!("expected a CREATE TABLE statement");
+        };
+        let Constraint::Check(Expression::BinaryOperation { right_operand, .. }, _) = &column_list[0].constraints[0] else {
+            panic!("expected a CHECK constraint");
+        };
+        let offset = right_operand.span().start;
+
+        tree.token_mut(offset).unwrap().set_text("21");
+        assert_eq!(tree.to_string(), "CREATE TABLE t (\n  age INT CHECK (age >= 21) -- adults\n);");
+        assert!(tree.token_mut(offset + 1).is_none());
+    }
+}
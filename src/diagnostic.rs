@@ -0,0 +1,119 @@
+use std::fmt::{Display, Formatter};
+
+use crate::error::ParseError;
+
+/// Renders a `ParseError` the way compilers do: the message, the location, the offending line of SQL with the bad token underlined with `^^^`, and a hint on how to fix it when one is known.
+///
+/// ```text
+/// error: Expected ';', found end of input
+///  --> query.sql:1:21
+///   |
+/// 1 | SELECT id FROM users
+///   |                     ^
+///   = hint: SELECT statements must end with ';'
+/// ```
+///
+/// `source` must be the text the error was produced from, since the span of the error is resolved against it.
+pub struct Diagnostic<'a> {
+    error: &'a ParseError,
+    source: &'a str,
+    origin: Option<&'a str>,
+}
+
+impl<'a> Diagnostic<'a> {
+    pub fn new(error: &'a ParseError, source: &'a str) -> Self {
+        Self {
+            error,
+            source,
+            origin: None,
+        }
+    }
+
+    /// Sets the name shown in front of the location, usually a file name.
+    pub fn with_origin(mut self, origin: &'a str) -> Self {
+        self.origin = Some(origin);
+        self
+    }
+}
+
+impl Display for Diagnostic<'_> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        let span = self.error.span();
+        let start = span.start.min(self.source.len());
+        let end = span.end.clamp(start, self.source.len());
+
+        let line_start = self.source[..start].rfind('\n').map_or(0, |i| i + 1);
+        let line_end = self.source[start..].find('\n').map_or(self.source.len(), |i| start + i);
+        let line = self.source[line_start..line_end].trim_end_matches('\r');
+
+        // Keep tabs in the padding so the carets line up with the echoed line.
+        let padding: String = self.source[line_start..start]
+            .chars()
+            .map(|c| if c == '\t' { '\t' } else { ' ' })
+            .collect();
+        let width = self.source[start..end.min(line_end)].chars().count().max(1);
+
+        let line_number = span.line.to_string();
+        let gutter = " ".repeat(line_number.len());
+
+        writeln!(f, "error: {}", self.error.kind())?;
+        writeln!(
+            f,
+            "{}--> {}:{}:{}",
+            gutter,
+            self.origin.unwrap_or("<input>"),
+            span.line,
+            span.column
+        )?;
+        writeln!(f, "{} |", gutter)?;
+        writeln!(f, "{} | {}", line_number, line)?;
+        write!(f, "{} | {}{}", gutter, padding, "^".repeat(width))?;
+
+        if let Some(index) = self.error.statement_index() {
+            write!(f, "\n{} = note: in statement {}", gutter, index + 1)?;
+        }
+        if let Some(hint) = self.error.hint() {
+            write!(f, "\n{} = hint: {}", gutter, hint)?;
+        }
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::dialect::GenericDialect;
+    use crate::parser::Parser;
+
+    #[test]
+    fn test_missing_semicolon() {
+        let source = "CREATE TABLE work_hours(num_hours INT)";
+        let error = Parser::new(source, &GenericDialect).parse_statement().unwrap_err();
+
+        assert_eq!(Diagnostic::new(&error, source).to_string(), [
+            "error: Expected ';', found end of input",
+            " --> <input>:1:39",
+            "  |",
+            "1 | CREATE TABLE work_hours(num_hours INT)",
+            "  |                                       ^",
+            "  = hint: CREATE TABLE statements must end with ';'",
+        ].join("\n"));
+    }
+
+    #[test]
+    fn test_multiline_script() {
+        let source = "SELECT id FROM users;\nCREATE TABLE t(\n\tid INT,\n\tname TEXT\n);";
+        let error = Parser::new(source, &GenericDialect).parse_statements().unwrap_err();
+
+        assert_eq!(Diagnostic::new(&error, source).with_origin("schema.sql").to_string(), [
+            "error: Expected a data type, found identifier \"TEXT\"",
+            " --> schema.sql:4:7",
+            "  |",
+            "4 | \tname TEXT",
+            "  | \t     ^^^^",
+            "  = note: in statement 2",
+            "  = hint: supported data types are INT, VARCHAR(n), BOOL and BLOB (or BYTEA)",
+        ].join("\n"));
+    }
+}
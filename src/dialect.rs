@@ -0,0 +1,144 @@
+use crate::ident::CaseFolding;
+use crate::token::Keyword;
+
+/// Describes the flavour of SQL the tokenizer and the parser accept. A dialect decides how identifiers are quoted, how strings are escaped, which words are keywords, which operators exist and what `||` means. Every method has a default that follows ANSI SQL, so a dialect only overrides what it does differently.
+///
+/// `Tokenizer::new` and `Parser::new` both take a `&dyn Dialect`; the built in dialects are unit structs, so `&GenericDialect` can be passed directly.
+pub trait Dialect {
+    /// Returns `true` if `quote` opens a quoted identifier. The closing quote is the same character, except for `[`, which is closed by `]`.
+    fn is_identifier_quote(&self, quote: char) -> bool {
+        quote == '"'
+    }
+
+    /// Returns `true` if `quote` opens a string literal.
+    fn is_string_quote(&self, quote: char) -> bool {
+        quote == '\''
+    }
+
+    /// Returns `true` if backslash escapes (`\n`, `\t`, `\r`, `\0`, `\\`, `\'`, `\"`) are interpreted inside every string literal.
+    fn supports_backslash_escapes(&self) -> bool {
+        false
+    }
+
+    /// Returns `true` if `E'...'` strings, which always interpret backslash escapes, are accepted.
+    fn supports_escape_strings(&self) -> bool {
+        false
+    }
+
+    /// Returns how unquoted identifiers are normalized into `Ident::normalized`. Quoted identifiers are never folded.
+    fn case_folding(&self) -> CaseFolding {
+        CaseFolding::Preserve
+    }
+
+    /// Returns `true` if `keyword` is a keyword in this dialect. A word that is not a keyword is read as an identifier, so a dialect can free up names such as `bytea`.
+    fn is_keyword(&self, _keyword: &Keyword) -> bool {
+        true
+    }
+
+    /// Returns `true` if `keyword` is reserved, meaning it can never be used as an unquoted identifier. Non-reserved keywords (`KEY`, `CHECK`, `DESC`, the type names, ...) are accepted as column and table names; see `Keyword::is_reserved`.
+    fn is_reserved_keyword(&self, keyword: &Keyword) -> bool {
+        keyword.is_reserved()
+    }
+
+    /// Returns `true` if the operator spelled `operator` is supported. The optional operators are `<>` (not equal), `||` (see `pipes_as_concat`), `::` (type cast) and `%` (modulo); an unsupported one is reported as an invalid character.
+    fn supports_operator(&self, operator: &str) -> bool {
+        matches!(operator, "<>" | "||" | "%")
+    }
+
+    /// Returns `true` if `||` concatenates strings, `false` if it is a logical `OR` (MySQL's default).
+    fn pipes_as_concat(&self) -> bool {
+        true
+    }
+
+    /// Returns `true` if the statement starting with `keyword` (`SELECT` or `CREATE`) is supported. Every built in dialect supports both; a dialect for a read-only connection, for example, can reject `CREATE`, which is then reported as an unexpected token.
+    fn supports_statement(&self, _keyword: &Keyword) -> bool {
+        true
+    }
+}
+
+/// A permissive dialect that accepts the union of what the other dialects support: ANSI quoting, `E'...'` strings, every operator and both `BLOB` and `BYTEA`.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct GenericDialect;
+
+impl Dialect for GenericDialect {
+    fn supports_escape_strings(&self) -> bool {
+        true
+    }
+
+    fn supports_operator(&self, operator: &str) -> bool {
+        matches!(operator, "<>" | "||" | "::" | "%")
+    }
+}
+
+/// PostgreSQL: `"quoted"` identifiers, unquoted identifiers folded to lower case, `E'...'` escape strings, `::` casts and `BYTEA` (`blob` is an ordinary name).
+#[derive(Debug, Clone, Copy, Default)]
+pub struct PostgresDialect;
+
+impl Dialect for PostgresDialect {
+    fn case_folding(&self) -> CaseFolding {
+        CaseFolding::Lower
+    }
+
+    fn supports_escape_strings(&self) -> bool {
+        true
+    }
+
+    fn is_keyword(&self, keyword: &Keyword) -> bool {
+        *keyword != Keyword::Blob
+    }
+
+    fn supports_operator(&self, operator: &str) -> bool {
+        matches!(operator, "<>" | "||" | "::" | "%")
+    }
+}
+
+/// MySQL: `` `quoted` `` identifiers, strings in single or double quotes, backslash escapes in every string, `||` as a logical `OR` and `BLOB` (`bytea` is an ordinary name).
+#[derive(Debug, Clone, Copy, Default)]
+pub struct MySqlDialect;
+
+impl Dialect for MySqlDialect {
+    fn is_identifier_quote(&self, quote: char) -> bool {
+        quote == '`'
+    }
+
+    fn is_string_quote(&self, quote: char) -> bool {
+        quote == '\'' || quote == '"'
+    }
+
+    fn supports_backslash_escapes(&self) -> bool {
+        true
+    }
+
+    fn is_keyword(&self, keyword: &Keyword) -> bool {
+        *keyword != Keyword::Bytea
+    }
+
+    fn pipes_as_concat(&self) -> bool {
+        false
+    }
+}
+
+/// SQLite: identifiers quoted with `"`, `` ` `` or `[...]`, no backslash escapes and `BLOB` (`bytea` is an ordinary name).
+#[derive(Debug, Clone, Copy, Default)]
+pub struct SqliteDialect;
+
+impl Dialect for SqliteDialect {
+    fn is_identifier_quote(&self, quote: char) -> bool {
+        matches!(quote, '"' | '`' | '[')
+    }
+
+    fn is_keyword(&self, keyword: &Keyword) -> bool {
+        *keyword != Keyword::Bytea
+    }
+}
+
+/// Looks up a built in dialect by name (`generic`, `postgres`/`postgresql`, `mysql`, `sqlite`), ignoring case. Used by the command line `--dialect` flag.
+pub fn dialect_from_name(name: &str) -> Option<Box<dyn Dialect>> {
+    match name.to_ascii_lowercase().as_str() {
+        "generic" => Some(Box::new(GenericDialect)),
+        "postgres" | "postgresql" => Some(Box::new(PostgresDialect)),
+        "mysql" => Some(Box::new(MySqlDialect)),
+        "sqlite" => Some(Box::new(SqliteDialect)),
+        _ => None,
+    }
+}
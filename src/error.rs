@@ -0,0 +1,200 @@
+use std::error::Error;
+use std::fmt::{Display, Formatter};
+use std::io;
+
+use crate::span::Span;
+use crate::token::Token;
+
+/// The error returned by the tokenizer and the parser when a query cannot be parsed. The actual problem is described by a `ParseErrorKind`, which callers can match on to tell lexical errors (bad characters, unterminated strings, invalid numbers) apart from syntax errors (tokens in the wrong place).
+///
+/// Every error carries the `Span` of the offending token (or of the end of the input), so it can be pointed out in the original text.
+///
+/// When a whole script is parsed, `statement_index` holds the zero-based index of the statement that failed (empty statements like `;;` are not counted).
+#[derive(Debug, PartialEq, Clone)]
+pub struct ParseError {
+    /// Boxed to keep `Result<_, ParseError>` small on the parser's hot path.
+    kind: Box<ParseErrorKind>,
+    span: Span,
+    statement_index: Option<usize>,
+    hint: Option<&'static str>,
+}
+
+/// Every kind of error the tokenizer or the parser can report. `InvalidCharacter`, `UnterminatedString`, `UnterminatedIdentifier`, `UnterminatedComment`, `InvalidEscape` and `InvalidBinaryLiteral` are lexical errors produced by the tokenizer, `UnexpectedToken`, `UnexpectedEof` and `NumberOutOfRange` are syntax errors produced by the parser. `NumberOutOfRange` carries the full text of a numeric literal that is too large for the place it is used in, such as a `VARCHAR` length. The `expected` field is a short human readable description of what the grammar allows at that point, for example `';'` or `a data type`.
+#[derive(Debug, PartialEq, Clone)]
+pub enum ParseErrorKind {
+    InvalidCharacter(char),
+    UnterminatedString,
+    UnterminatedIdentifier,
+    UnterminatedComment,
+    InvalidEscape(String),
+    InvalidBinaryLiteral(String),
+    UnexpectedToken {
+        expected: String,
+        found: Token<'static>,
+    },
+    UnexpectedEof {
+        expected: String,
+    },
+    NumberOutOfRange(String),
+}
+
+impl ParseError {
+    pub fn new(kind: ParseErrorKind, span: Span) -> Self {
+        Self {
+            kind: Box::new(kind),
+            span,
+            statement_index: None,
+            hint: None,
+        }
+    }
+
+    /// Creates a syntax error for `found` appearing where `expected` should be. Running out of input is reported as `UnexpectedEof`. The token is copied out of the input, so errors never borrow from it.
+    pub fn unexpected(expected: impl Into<String>, found: Token<'_>, span: Span) -> Self {
+        let expected = expected.into();
+        match found {
+            Token::Eof => Self::new(ParseErrorKind::UnexpectedEof { expected }, span),
+            found => Self::new(ParseErrorKind::UnexpectedToken { expected, found: found.into_owned() }, span),
+        }
+    }
+
+    pub fn with_statement_index(mut self, index: usize) -> Self {
+        self.statement_index = Some(index);
+        self
+    }
+
+    /// Attaches a short suggestion on how to fix a syntax error, shown by `Diagnostic`. An existing hint is kept, so the innermost (most specific) hint wins, and lexical errors keep their generic hint.
+    pub fn with_hint(mut self, hint: &'static str) -> Self {
+        if self.is_syntax() {
+            self.hint.get_or_insert(hint);
+        }
+        self
+    }
+
+    pub fn kind(&self) -> &ParseErrorKind {
+        &self.kind
+    }
+
+    pub fn span(&self) -> Span {
+        self.span
+    }
+
+    pub fn statement_index(&self) -> Option<usize> {
+        self.statement_index
+    }
+
+    pub fn hint(&self) -> Option<&'static str> {
+        self.hint.or_else(|| self.kind.hint())
+    }
+
+    /// Returns `true` for errors produced by the tokenizer.
+    pub fn is_lexical(&self) -> bool {
+        self.kind.is_lexical()
+    }
+
+    /// Returns `true` for errors produced by the parser.
+    pub fn is_syntax(&self) -> bool {
+        !self.kind.is_lexical()
+    }
+}
+
+impl ParseErrorKind {
+    pub fn is_lexical(&self) -> bool {
+        matches!(
+            self,
+            ParseErrorKind::InvalidCharacter(_)
+                | ParseErrorKind::UnterminatedString
+                | ParseErrorKind::UnterminatedIdentifier
+                | ParseErrorKind::UnterminatedComment
+                | ParseErrorKind::InvalidEscape(_)
+                | ParseErrorKind::InvalidBinaryLiteral(_)
+        )
+    }
+
+    /// A generic hint for the kind of error, used when the parser did not attach a more specific one.
+    fn hint(&self) -> Option<&'static str> {
+        match self {
+            ParseErrorKind::InvalidCharacter(_) => Some("this character is not part of any SQL token"),
+            ParseErrorKind::UnterminatedString => Some("strings must be closed with the same quote they were opened with"),
+            ParseErrorKind::UnterminatedIdentifier => Some("quoted identifiers must be closed with the same quote they were opened with"),
+            ParseErrorKind::UnterminatedComment => Some("block comments must be closed with '*/', nested comments need one '*/' each"),
+            ParseErrorKind::InvalidEscape(_) => Some(r#"supported escapes are \n, \t, \r, \0, \\, \' and \""#),
+            ParseErrorKind::InvalidBinaryLiteral(_) => Some("hex literals may only contain 0-9 and A-F, bit strings only 0 and 1"),
+            ParseErrorKind::NumberOutOfRange(_) => Some("this number must fit in an unsigned 64-bit integer"),
+            ParseErrorKind::UnexpectedToken { .. } | ParseErrorKind::UnexpectedEof { .. } => None,
+        }
+    }
+}
+
+impl Display for ParseErrorKind {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            ParseErrorKind::InvalidCharacter(c) => write!(f, "Invalid character '{}'", c),
+            ParseErrorKind::UnterminatedString => write!(f, "Unterminated string literal"),
+            ParseErrorKind::UnterminatedIdentifier => write!(f, "Unterminated quoted identifier"),
+            ParseErrorKind::UnterminatedComment => write!(f, "Unterminated block comment"),
+            ParseErrorKind::InvalidEscape(escape) => write!(f, "Invalid escape sequence {}", escape),
+            ParseErrorKind::InvalidBinaryLiteral(literal) => write!(f, "Invalid binary literal {}", literal),
+            ParseErrorKind::UnexpectedToken { expected, found } => write!(f, "Expected {}, found {}", expected, describe(found)),
+            ParseErrorKind::UnexpectedEof { expected } => write!(f, "Expected {}, found end of input", expected),
+            ParseErrorKind::NumberOutOfRange(number) => write!(f, "Numeric literal {} is out of range", number),
+        }
+    }
+}
+
+fn describe(token: &Token<'_>) -> String {
+    match token {
+        Token::Keyword(keyword) => format!("keyword {}", keyword),
+        Token::Identifier(_) => format!("identifier {}", token),
+        Token::String(_) | Token::HexString(_) | Token::BitString(_) => format!("string {}", token),
+        Token::Number(_) | Token::Decimal(_) => format!("number {}", token),
+        _ => format!("'{}'", token),
+    }
+}
+
+impl Display for ParseError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        if let Some(index) = self.statement_index {
+            write!(f, "Statement {}: ", index + 1)?;
+        }
+        write!(f, "{} at line {}, column {}", self.kind, self.span.line, self.span.column)
+    }
+}
+
+impl Error for ParseError {}
+
+/// The error yielded by `StatementReader`: either reading the input failed (which includes input that is not valid UTF-8), or a statement could not be parsed. A parse error only affects its own statement, so reading can carry on with the next one; after an I/O error the reader stops.
+#[derive(Debug)]
+pub enum ReadError {
+    Io(io::Error),
+    Parse(ParseError),
+}
+
+impl Display for ReadError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            ReadError::Io(error) => write!(f, "Failed to read input: {}", error),
+            ReadError::Parse(error) => write!(f, "{}", error),
+        }
+    }
+}
+
+impl Error for ReadError {
+    fn source(&self) -> Option<&(dyn Error + 'static)> {
+        match self {
+            ReadError::Io(error) => Some(error),
+            ReadError::Parse(error) => Some(error),
+        }
+    }
+}
+
+impl From<io::Error> for ReadError {
+    fn from(error: io::Error) -> Self {
+        ReadError::Io(error)
+    }
+}
+
+impl From<ParseError> for ReadError {
+    fn from(error: ParseError) -> Self {
+        ReadError::Parse(error)
+    }
+}
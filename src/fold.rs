@@ -0,0 +1,272 @@
+use crate::statement::{Constraint, Expression, Statement, TableColumn};
+
+/// The result of a rewrite: the rebuilt value and whether the rewrite changed anything in it. A rewrite function returns `Transformed::yes(replacement)` when it replaces a node and `Transformed::no(node)` to keep it; `transform_up` and `transform_down` combine the flags of every node they visit.
+#[derive(Debug, PartialEq)]
+pub struct Transformed<T> {
+    pub value: T,
+    pub changed: bool,
+}
+
+impl<T> Transformed<T> {
+    /// A value that was rewritten.
+    pub fn yes(value: T) -> Self {
+        Self { value, changed: true }
+    }
+
+    /// A value that was kept as it was.
+    pub fn no(value: T) -> Self {
+        Self { value, changed: false }
+    }
+
+    /// Applies `f` to the value, keeping the flag.
+    pub fn map<U>(self, f: impl FnOnce(T) -> U) -> Transformed<U> {
+        Transformed {
+            value: f(self.value),
+            changed: self.changed,
+        }
+    }
+}
+
+impl<'a> Expression<'a> {
+    /// Rebuilds the expression bottom-up: the operands of every node are rewritten before `f` sees the node itself, so `f` always receives a node whose children are final. Use this for simplifications that depend on rewritten operands, such as constant folding.
+    ///
+    /// A node built by `f` keeps whatever span `f` gives it; the spans of untouched nodes stay as parsed.
+    ///
+    /// ```
+    /// use angunna_gamage_thilina_ranmal::{parse_sql, Expression, Ident, Statement, Transformed};
+    ///
+    /// let statement = parse_sql("SELECT a FROM t WHERE a > b;").unwrap().remove(0);
+    /// let Statement::Select { r#where: Some(condition), .. } = statement else { unreachable!() };
+    /// let renamed = condition.transform_up(|expression| match expression {
+    ///     Expression::Identifier(ident, span) if ident.value == "b" => Transformed::yes(Expression::Identifier(Ident::new("c"), span)),
+    ///     expression => Transformed::no(expression),
+    /// });
+    /// assert!(renamed.changed);
+    /// assert_eq!(renamed.value.to_string(), "a > c");
+    /// ```
+    pub fn transform_up<F>(self, mut f: F) -> Transformed<Expression<'a>>
+    where
+        F: FnMut(Expression<'a>) -> Transformed<Expression<'a>>,
+    {
+        transform_up(self, &mut f)
+    }
+
+    /// Rebuilds the expression top-down: `f` sees every node before its operands, and the operands of whatever `f` returns are rewritten next. Use this when a rewrite should see the original shape of a subtree, for example to replace a whole `a AND b` before `a` and `b` are rewritten on their own.
+    ///
+    /// Because the result of `f` is visited further, `f` must not match its own output: a rewrite that wraps `x` into `x AND y` would wrap the inner `x` again, forever. Such rewrites belong in `transform_up`.
+    pub fn transform_down<F>(self, mut f: F) -> Transformed<Expression<'a>>
+    where
+        F: FnMut(Expression<'a>) -> Transformed<Expression<'a>>,
+    {
+        transform_down(self, &mut f)
+    }
+
+    /// Rebuilds the node with every direct operand passed through `f`.
+    fn map_operands<F>(self, mut f: F) -> Transformed<Expression<'a>>
+    where
+        F: FnMut(Expression<'a>) -> Transformed<Expression<'a>>,
+    {
+        match self {
+            Expression::BinaryOperation { left_operand, operator, right_operand, span } => {
+                let left = f(*left_operand);
+                let right = f(*right_operand);
+                Transformed {
+                    changed: left.changed || right.changed,
+                    value: Expression::BinaryOperation {
+                        left_operand: Box::new(left.value),
+                        operator,
+                        right_operand: Box::new(right.value),
+                        span,
+                    },
+                }
+            },
+            Expression::UnaryOperation { operand, operator, span } => f(*operand).map(|operand| Expression::UnaryOperation {
+                operand: Box::new(operand),
+                operator,
+                span,
+            }),
+            Expression::Cast { expression, data_type, span } => f(*expression).map(|expression| Expression::Cast {
+                expression: Box::new(expression),
+                data_type,
+                span,
+            }),
+            leaf => Transformed::no(leaf),
+        }
+    }
+}
+
+fn transform_up<'a, F>(expression: Expression<'a>, f: &mut F) -> Transformed<Expression<'a>>
+where
+    F: FnMut(Expression<'a>) -> Transformed<Expression<'a>>,
+{
+    let operands = expression.map_operands(|operand| transform_up(operand, f));
+    let mut node = f(operands.value);
+    node.changed |= operands.changed;
+    node
+}
+
+fn transform_down<'a, F>(expression: Expression<'a>, f: &mut F) -> Transformed<Expression<'a>>
+where
+    F: FnMut(Expression<'a>) -> Transformed<Expression<'a>>,
+{
+    let node = f(expression);
+    let mut operands = node.value.map_operands(|operand| transform_down(operand, f));
+    operands.changed |= node.changed;
+    operands
+}
+
+impl<'a> Statement<'a> {
+    /// Rewrites every expression in the statement with `Expression::transform_up`: the select list, the `WHERE` condition, the `ORDER BY` items and the `CHECK` constraints.
+    ///
+    /// ```
+    /// use angunna_gamage_thilina_ranmal::{parse_sql, BinaryOperator, Expression, Transformed};
+    ///
+    /// let statement = parse_sql("SELECT a FROM t WHERE a <> 1;").unwrap().remove(0);
+    /// let rewritten = statement.transform_up(|expression| match expression {
+    ///     Expression::BinaryOperation { left_operand, operator: BinaryOperator::NotEqual, right_operand, span } => {
+    ///         Transformed::yes(Expression::BinaryOperation { left_operand, operator: BinaryOperator::LessThan, right_operand, span })
+    ///     },
+    ///     expression => Transformed::no(expression),
+    /// });
+    /// assert_eq!(rewritten.value.to_string(), "SELECT a FROM t WHERE a < 1;");
+    /// ```
+    pub fn transform_up<F>(self, mut f: F) -> Transformed<Statement<'a>>
+    where
+        F: FnMut(Expression<'a>) -> Transformed<Expression<'a>>,
+    {
+        self.map_expressions(|expression| expression.transform_up(&mut f))
+    }
+
+    /// Rewrites every expression in the statement with `Expression::transform_down`.
+    pub fn transform_down<F>(self, mut f: F) -> Transformed<Statement<'a>>
+    where
+        F: FnMut(Expression<'a>) -> Transformed<Expression<'a>>,
+    {
+        self.map_expressions(|expression| expression.transform_down(&mut f))
+    }
+
+    /// Rebuilds the statement with every top level expression passed through `f`.
+    fn map_expressions<F>(self, mut f: F) -> Transformed<Statement<'a>>
+    where
+        F: FnMut(Expression<'a>) -> Transformed<Expression<'a>>,
+    {
+        let mut changed = false;
+        let mut rewrite = |expression| {
+            let rewritten = f(expression);
+            changed |= rewritten.changed;
+            rewritten.value
+        };
+        let statement = match self {
+            Statement::Select { columns, from, r#where, orderby, span } => Statement::Select {
+                columns: columns.into_iter().map(&mut rewrite).collect(),
+                from,
+                r#where: r#where.map(&mut rewrite),
+                orderby: orderby.into_iter().map(&mut rewrite).collect(),
+                span,
+            },
+            Statement::CreateTable { table_name, column_list, span } => Statement::CreateTable {
+                table_name,
+                column_list: column_list
+                    .into_iter()
+                    .map(|column| TableColumn {
+                        constraints: column
+                            .constraints
+                            .into_iter()
+                            .map(|constraint| match constraint {
+                                Constraint::Check(expression, span) => Constraint::Check(rewrite(expression), span),
+                                constraint => constraint,
+                            })
+                            .collect(),
+                        ..column
+                    })
+                    .collect(),
+                span,
+            },
+        };
+        Transformed { value: statement, changed }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::parse_sql;
+    use crate::span::Span;
+    use crate::statement::{BinaryOperator, UnaryOperator};
+
+    fn statement(sql: &str) -> Statement<'_> {
+        parse_sql(sql).unwrap().remove(0)
+    }
+
+    /// Adds up `+` operations on two numbers.
+    fn fold_constants(expression: Expression<'_>) -> Transformed<Expression<'_>> {
+        match expression {
+            Expression::BinaryOperation { left_operand, operator: BinaryOperator::Plus, right_operand, span } => match (*left_operand, *right_operand) {
+                (Expression::Number(left, _), Expression::Number(right, _)) => Transformed::yes(Expression::Number(left + right, span)),
+                (left, right) => Transformed::no(Expression::BinaryOperation {
+                    left_operand: Box::new(left),
+                    operator: BinaryOperator::Plus,
+                    right_operand: Box::new(right),
+                    span,
+                }),
+            },
+            expression => Transformed::no(expression),
+        }
+    }
+
+    #[test]
+    fn test_transform_up() {
+        let rewritten = statement("SELECT 1 + 2 + 3, a + 1 FROM t WHERE a > (1 + 1) * 2;").transform_up(fold_constants);
+        assert!(rewritten.changed);
+        assert_eq!(rewritten.value.to_string(), "SELECT 6, a + 1 FROM t WHERE a > 2 * 2;");
+
+        let unchanged = statement("SELECT a + 1 FROM t;").transform_up(fold_constants);
+        assert!(!unchanged.changed);
+        assert_eq!(unchanged.value, statement("SELECT a + 1 FROM t;"));
+    }
+
+    /// Rewrites `NOT (a AND b)` into `NOT a OR NOT b`.
+    fn de_morgan(expression: Expression<'_>) -> Transformed<Expression<'_>> {
+        let Expression::UnaryOperation { operand, operator: UnaryOperator::Not, span } = expression else {
+            return Transformed::no(expression);
+        };
+        match *operand {
+            Expression::BinaryOperation { left_operand, operator: BinaryOperator::And, right_operand, .. } => {
+                let not = |operand| Box::new(Expression::UnaryOperation { operand, operator: UnaryOperator::Not, span });
+                Transformed::yes(Expression::BinaryOperation {
+                    left_operand: not(left_operand),
+                    operator: BinaryOperator::Or,
+                    right_operand: not(right_operand),
+                    span,
+                })
+            },
+            operand => Transformed::no(Expression::UnaryOperation { operand: Box::new(operand), operator: UnaryOperator::Not, span }),
+        }
+    }
+
+    #[test]
+    fn test_transform_down() {
+        // Top-down, the replacement's operands are visited, so the nested NOT is rewritten too.
+        let rewritten = statement("SELECT a FROM t WHERE NOT (a AND (b AND c));").transform_down(de_morgan);
+        assert!(rewritten.changed);
+        assert_eq!(rewritten.value.to_string(), "SELECT a FROM t WHERE NOT a OR (NOT b OR NOT c);");
+
+        // Bottom-up, the nested NOT is created after its operands were visited.
+        let rewritten = statement("SELECT a FROM t WHERE NOT (a AND (b AND c));").transform_up(de_morgan);
+        assert_eq!(rewritten.value.to_string(), "SELECT a FROM t WHERE NOT a OR NOT (b AND c);");
+    }
+
+    #[test]
+    fn test_create_table() {
+        let rename = |expression| match expression {
+            Expression::Identifier(ident, span) if ident.value == "a" => Transformed::yes(Expression::Identifier(crate::Ident::new("b"), span)),
+            expression => Transformed::no(expression),
+        };
+        let rewritten = statement("CREATE TABLE t (a INT NOT NULL CHECK (a > 0), c INT);").transform_up(rename);
+        assert!(rewritten.changed);
+        assert_eq!(rewritten.value.to_string(), "CREATE TABLE t (a INT NOT NULL CHECK (b > 0), c INT);");
+
+        let expression = Expression::Bool(true, Span::default());
+        assert_eq!(expression.transform_down(rename), Transformed::no(Expression::Bool(true, Span::default())));
+    }
+}
@@ -0,0 +1,301 @@
+use std::collections::HashSet;
+use std::ops::ControlFlow;
+
+use crate::cst::{SyntaxElement, SyntaxKind, SyntaxTree};
+use crate::dialect::Dialect;
+use crate::error::ParseError;
+use crate::parser::Parser;
+use crate::statement::{BinaryOperator, Expression, Statement, TableColumn};
+use crate::token::{Keyword, Token};
+use crate::tokenizer::{Tokenizer, TokenizerOptions};
+use crate::visitor::Visitor;
+
+/// How `format_sql` lays out SQL.
+/// * `keyword_case` – whether keywords (including `AND`, `TRUE`, `NULL` and the type names) are written in upper or lower case. Identifiers and strings are never changed.
+/// * `indent` – the number of spaces `SELECT` columns, `CREATE TABLE` column definitions and wrapped `WHERE` conditions are indented by.
+/// * `max_width` – a `SELECT` statement that fits in this many characters stays on one line; a longer one gets one column per line, and a `WHERE` clause that is still too long is broken before every top level `AND` and `OR`.
+#[derive(Debug, Clone, Copy)]
+pub struct FormatOptions {
+    pub keyword_case: KeywordCase,
+    pub indent: usize,
+    pub max_width: usize,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum KeywordCase {
+    #[default]
+    Upper,
+    Lower,
+}
+
+impl Default for FormatOptions {
+    fn default() -> Self {
+        Self {
+            keyword_case: KeywordCase::Upper,
+            indent: 4,
+            max_width: 80,
+        }
+    }
+}
+
+/// Formats a whole SQL file. Every statement is laid out by `format_statement` and starts on a new line; comments between statements are kept, as are blank lines (collapsed to one). A statement that contains a comment, or that `format_statement` cannot format, is kept exactly as written. Returns every parse error instead if any part of the input does not parse, so a file is never rewritten from a partial parse.
+pub fn format_sql(input: &str, dialect: &dyn Dialect, options: &FormatOptions) -> Result<String, Vec<ParseError>> {
+    let tree = SyntaxTree::parse(input, dialect);
+    if !tree.errors().is_empty() {
+        return Err(tree.errors().to_vec());
+    }
+
+    let mut output = String::new();
+    let mut newlines = 0;
+    let mut statements = tree.statements().iter();
+    for child in tree.root().children() {
+        match child {
+            SyntaxElement::Token(token) => match token.token() {
+                Some(Token::Whitespace(whitespace)) => newlines = whitespace.matches('\n').count(),
+                Some(Token::Comment(comment)) => {
+                    separate(&mut output, newlines, false);
+                    output.push_str(comment);
+                    newlines = 0;
+                },
+                // The `;` of an empty statement.
+                _ => {},
+            },
+            SyntaxElement::Node(node) if node.kind() == SyntaxKind::Statement => {
+                let Some(statement) = statements.next() else {
+                    break;
+                };
+                separate(&mut output, newlines, true);
+                let has_comments = node.tokens().iter().any(|token| matches!(token.token(), Some(Token::Comment(_))));
+                match format_statement(statement, dialect, options) {
+                    Some(formatted) if !has_comments => output.push_str(&formatted),
+                    _ => output.push_str(&node.to_string()),
+                }
+                newlines = 0;
+            },
+            SyntaxElement::Node(_) => {},
+        }
+    }
+
+    if !output.is_empty() {
+        output.push('\n');
+    }
+    Ok(output)
+}
+
+/// Writes the separator in front of the next statement or comment: a blank line if the input had one, otherwise a line break. A comment that followed on the same line stays there.
+fn separate(output: &mut String, newlines: usize, statement: bool) {
+    if output.is_empty() {
+        return;
+    }
+    match newlines {
+        0 if !statement => output.push(' '),
+        0 | 1 => output.push('\n'),
+        _ => output.push_str("\n\n"),
+    }
+}
+
+/// Lays out one statement. `CREATE TABLE` gets one column definition per line; `SELECT` stays on one line if it fits in `max_width`, and otherwise gets one column per line and a clause per line. Everything is rendered for `dialect` (see `Statement::sql`), and the result is checked to parse back into the same statement in it; `None` is returned if it would not.
+pub fn format_statement(statement: &Statement<'_>, dialect: &dyn Dialect, options: &FormatOptions) -> Option<String> {
+    let indent = " ".repeat(options.indent);
+    let layout = match statement {
+        Statement::CreateTable { table_name, column_list, .. } => {
+            let columns: Vec<String> = column_list.iter().map(|column| format!("{}{}", indent, column.sql(dialect))).collect();
+            format!("CREATE TABLE {} (\n{}\n);", table_name.sql(dialect), columns.join(",\n"))
+        },
+        Statement::Select { .. } if fits(&statement.sql(dialect).to_string(), options) => statement.sql(dialect).to_string(),
+        Statement::Select { columns, from, r#where, orderby, .. } => {
+            let columns: Vec<String> = columns.iter().map(|column| format!("{}{}", indent, column.sql(dialect))).collect();
+            let mut layout = format!("SELECT\n{}\nFROM {}", columns.join(",\n"), from.sql(dialect));
+            if let Some(condition) = r#where {
+                let clause = format!("WHERE {}", condition.sql(dialect));
+                if fits(&clause, options) {
+                    layout.push_str(&format!("\n{}", clause));
+                } else {
+                    layout.push_str(&format!("\nWHERE {}", boolean_chain(condition, dialect).join(&format!("\n{}", indent))));
+                }
+            }
+            if !orderby.is_empty() {
+                let items: Vec<String> = orderby.iter().map(|item| item.sql(dialect).to_string()).collect();
+                layout.push_str(&format!("\nORDER BY {}", items.join(", ")));
+            }
+            layout.push(';');
+            layout
+        },
+    };
+
+    let formatted = apply_keyword_case(&layout, dialect, options.keyword_case)?;
+    match Parser::new(&formatted, dialect).parse_statement() {
+        Ok(reparsed) if reparsed == *statement => Some(formatted),
+        _ => None,
+    }
+}
+
+fn fits(line: &str, options: &FormatOptions) -> bool {
+    line.chars().count() <= options.max_width
+}
+
+/// Splits a condition before every `AND` and `OR` of its top level chain, so `a = 1 AND b = 2 OR c` becomes `["a = 1", "AND b = 2", "OR c"]`. Joined with spaces, the parts are exactly the rendering for `dialect`, so they parse back into the same tree.
+fn boolean_chain(condition: &Expression<'_>, dialect: &dyn Dialect) -> Vec<String> {
+    let Expression::BinaryOperation { left_operand, operator: operator @ (BinaryOperator::And | BinaryOperator::Or), right_operand, .. } = condition else {
+        return vec![condition.sql(dialect).to_string()];
+    };
+
+    let precedence = operator.precedence();
+    let mut parts = if left_operand.precedence() < precedence {
+        vec![format!("({})", left_operand.sql(dialect))]
+    } else {
+        boolean_chain(left_operand, dialect)
+    };
+    if right_operand.precedence() <= precedence {
+        parts.push(format!("{} ({})", operator, right_operand.sql(dialect)));
+    } else {
+        parts.push(format!("{} {}", operator, right_operand.sql(dialect)));
+    }
+    parts
+}
+
+/// Rewrites the keywords in `sql`, a single statement, to `case`, leaving identifiers, strings and everything else untouched. A non-reserved keyword the parser reads as a name, such as the column in `SELECT Key FROM t`, is an identifier and keeps its spelling: the statement is parsed to find where its names start, and the token after `FROM` and `TABLE` is always a name. Returns `None` if `sql` does not parse.
+fn apply_keyword_case(sql: &str, dialect: &dyn Dialect, case: KeywordCase) -> Option<String> {
+    let statement = Parser::new(sql, dialect).parse_statement().ok()?;
+    let mut names = NameStarts::default();
+    let _ = names.visit_statement(&statement);
+
+    let options = TokenizerOptions {
+        emit_comments: true,
+        emit_whitespace: true,
+        ..Default::default()
+    };
+    let mut output = String::with_capacity(sql.len());
+    let mut after_table_keyword = false;
+    for result in Tokenizer::with_options(sql, dialect, options) {
+        let (token, span) = result.ok()?;
+        let text = &sql[span.range()];
+        let is_name = after_table_keyword || names.0.contains(&span.start);
+        match (&token, case) {
+            (Token::Keyword(_), _) if is_name => output.push_str(text),
+            (Token::Keyword(_), KeywordCase::Upper) => output.push_str(&text.to_ascii_uppercase()),
+            (Token::Keyword(_), KeywordCase::Lower) => output.push_str(&text.to_ascii_lowercase()),
+            _ => output.push_str(text),
+        }
+        match token {
+            Token::Whitespace(_) | Token::Comment(_) => {},
+            token => after_table_keyword = matches!(token, Token::Keyword(Keyword::From | Keyword::Table)),
+        }
+    }
+    Some(output)
+}
+
+/// Collects the offsets where the identifiers in expressions and the column names of a statement start.
+#[derive(Default)]
+struct NameStarts(HashSet<usize>);
+
+impl<'ast> Visitor<'ast> for NameStarts {
+    type Break = ();
+
+    fn pre_visit_expression(&mut self, expression: &'ast Expression<'_>) -> ControlFlow<()> {
+        if let Expression::Identifier(_, span) = expression {
+            self.0.insert(span.start);
+        }
+        ControlFlow::Continue(())
+    }
+
+    fn pre_visit_table_column(&mut self, column: &'ast TableColumn<'_>) -> ControlFlow<()> {
+        self.0.insert(column.span.start);
+        ControlFlow::Continue(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::dialect::{GenericDialect, MySqlDialect, PostgresDialect, SqliteDialect};
+    use crate::error::ParseErrorKind;
+
+    fn format(input: &str, options: &FormatOptions) -> String {
+        let formatted = format_sql(input, &GenericDialect, options).unwrap();
+        assert_eq!(format_sql(&formatted, &GenericDialect, options).unwrap(), formatted, "formatting is not idempotent");
+        formatted
+    }
+
+    #[test]
+    fn test_select() {
+        let options = FormatOptions::default();
+        assert_eq!(format("select id,name from users where id>1;", &options), "SELECT id, name FROM users WHERE id > 1;\n");
+
+        let input = "select id, first_name, last_name, email_address from registered_users where age >= 18 order by id desc;";
+        assert_eq!(
+            format(input, &options),
+            "SELECT\n    id,\n    first_name,\n    last_name,\n    email_address\nFROM registered_users\nWHERE age >= 18\nORDER BY id DESC;\n"
+        );
+    }
+
+    #[test]
+    fn test_where_wrapping() {
+        let options = FormatOptions { max_width: 30, indent: 2, ..Default::default() };
+        let input = "SELECT id FROM users WHERE age >= 18 AND (name = 'a' OR name = 'b') AND active = TRUE OR admin;";
+        assert_eq!(
+            format(input, &options),
+            "SELECT\n  id\nFROM users\nWHERE age >= 18\n  AND (name = 'a' OR name = 'b')\n  AND active = TRUE\n  OR admin;\n"
+        );
+    }
+
+    #[test]
+    fn test_create_table() {
+        let options = FormatOptions { keyword_case: KeywordCase::Lower, ..Default::default() };
+        assert_eq!(
+            format("CREATE TABLE t(id INT PRIMARY KEY, name VARCHAR(10) NOT NULL CHECK(name != 'AND'));", &options),
+            "create table t (\n    id int primary key,\n    name varchar(10) not null check (name != 'AND')\n);\n"
+        );
+    }
+
+    #[test]
+    fn test_keywords_used_as_names() {
+        let options = FormatOptions::default();
+        assert_eq!(format("select Key, b from t;", &options), "SELECT Key, b FROM t;\n");
+        assert_eq!(format("select a from Check order by desc desc;", &options), "SELECT a FROM Check ORDER BY desc DESC;\n");
+        assert_eq!(format("create table Key (Check int, b int check (Check > 0));", &options), "CREATE TABLE Key (\n    Check INT,\n    b INT CHECK (Check > 0)\n);\n");
+
+        let options = FormatOptions { keyword_case: KeywordCase::Lower, ..Default::default() };
+        assert_eq!(format("SELECT KEY FROM t ORDER BY DESC DESC;", &options), "select KEY from t order by DESC desc;\n");
+    }
+
+    #[test]
+    fn test_comments_and_blank_lines() {
+        let options = FormatOptions::default();
+        let input = "-- users\nselect id from users; -- all of them\n\n\n/* keep */ select id from t;select id /* inside */ from  t;";
+        assert_eq!(
+            format(input, &options),
+            "-- users\nSELECT id FROM users; -- all of them\n\n/* keep */\nSELECT id FROM t;\nselect id /* inside */ from  t;\n"
+        );
+        assert_eq!(format("", &options), "");
+    }
+
+    #[test]
+    fn test_errors() {
+        let errors = format_sql("SELECT FROM t; SELECT 'open", &GenericDialect, &FormatOptions::default()).unwrap_err();
+        assert_eq!(errors[0].statement_index(), Some(0));
+        assert!(errors.iter().any(|error| error.kind() == &ParseErrorKind::UnterminatedString));
+    }
+
+    #[test]
+    fn test_meaning_is_preserved() {
+        let options = FormatOptions::default();
+        let formatted = format_sql(r"select `a` from t where b = 'x\'y';", &MySqlDialect, &options).unwrap();
+        assert_eq!(formatted, "SELECT `a` FROM t WHERE b = 'x''y';\n");
+
+        let formatted = format_sql(r"select a from t where b = 'x\\y';", &MySqlDialect, &options).unwrap();
+        assert_eq!(formatted, "SELECT a FROM t WHERE b = 'x\\\\y';\n");
+    }
+
+    #[test]
+    fn test_dialect_specific_statements() {
+        let options = FormatOptions::default();
+        let formatted = format_sql("create table files (id int primary key, data bytea not null);\n", &PostgresDialect, &options).unwrap();
+        assert_eq!(formatted, "CREATE TABLE files (\n    id INT PRIMARY KEY,\n    data BYTEA NOT NULL\n);\n");
+
+        let formatted = format_sql("select [a b] from [t] where c::int > 1;", &SqliteDialect, &options);
+        assert!(formatted.is_err(), "SQLite has no '::' casts");
+        let formatted = format_sql("select [a b], `c` from [t];", &SqliteDialect, &options).unwrap();
+        assert_eq!(formatted, "SELECT [a b], `c` FROM [t];\n");
+    }
+}
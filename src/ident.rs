@@ -0,0 +1,137 @@
+use std::borrow::Cow;
+use std::fmt::{Display, Formatter};
+
+/// An identifier (a table or column name) as it was written in the query. `value` is the original spelling and `quote_style` records the quote character the identifier was delimited with: `"` (ANSI), `` ` `` (MySQL) or `[` (SQL Server), or `None` for a plain identifier. Quoted identifiers keep their exact spelling and may contain spaces or be keywords, for example `"order"` or `[my table]`.
+///
+/// `normalized` is the name downstream consumers should use as a key: the spelling folded according to the `CaseFolding` policy the identifier was read with. Only unquoted identifiers are folded, so with `CaseFolding::Lower` both `Users` and `users` normalize to `users`, while `"Users"` stays `Users`.
+///
+/// Unquoted identifiers may contain any Unicode letters (`ąžuolas`, `äpfel`, `テーブル`); folding uses the full Unicode case mapping, so `ÄPFEL` folds to `äpfel`. `matches` compares two identifiers the same way regardless of the policy. No Unicode normalization (NFC/NFKC) is applied, so two different encodings of the same accented letter are different names.
+///
+/// Both strings borrow from the parsed input whenever possible; `normalized` only allocates when folding actually changes the spelling. `into_owned` detaches an identifier from the input.
+#[derive(Debug, PartialEq, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Ident<'a> {
+    pub value: Cow<'a, str>,
+    pub quote_style: Option<char>,
+    pub normalized: Cow<'a, str>,
+}
+
+/// How unquoted identifiers are normalized. PostgreSQL folds to lower case, the SQL standard (and Oracle) to upper case; `Preserve` keeps the spelling as written. The dialect picks the default, and `TokenizerOptions::case_folding` overrides it.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum CaseFolding {
+    #[default]
+    Preserve,
+    Lower,
+    Upper,
+}
+
+impl CaseFolding {
+    /// Folds `value`, borrowing it unchanged when it already is in the target case.
+    pub fn apply<'a>(&self, value: &Cow<'a, str>) -> Cow<'a, str> {
+        match self {
+            CaseFolding::Lower if value.chars().any(|c| !c.to_lowercase().eq([c])) => Cow::Owned(value.to_lowercase()),
+            CaseFolding::Upper if value.chars().any(|c| !c.to_uppercase().eq([c])) => Cow::Owned(value.to_uppercase()),
+            _ => value.clone(),
+        }
+    }
+}
+
+impl<'a> Ident<'a> {
+    /// Creates an unquoted identifier whose normalized form is its spelling.
+    pub fn new(value: impl Into<Cow<'a, str>>) -> Self {
+        Self::folded(value, CaseFolding::Preserve)
+    }
+
+    /// Creates an unquoted identifier normalized with `folding`.
+    pub fn folded(value: impl Into<Cow<'a, str>>, folding: CaseFolding) -> Self {
+        let value = value.into();
+        Self {
+            normalized: folding.apply(&value),
+            value,
+            quote_style: None,
+        }
+    }
+
+    /// Creates an identifier delimited with `quote` (`"`, `` ` `` or `[`). Quoted identifiers are never folded.
+    pub fn with_quote(quote: char, value: impl Into<Cow<'a, str>>) -> Self {
+        let value = value.into();
+        Self {
+            normalized: value.clone(),
+            value,
+            quote_style: Some(quote),
+        }
+    }
+
+    /// Copies any text borrowed from the input, so the identifier can outlive it.
+    pub fn into_owned(self) -> Ident<'static> {
+        Ident {
+            value: Cow::Owned(self.value.into_owned()),
+            quote_style: self.quote_style,
+            normalized: Cow::Owned(self.normalized.into_owned()),
+        }
+    }
+
+    pub fn is_quoted(&self) -> bool {
+        self.quote_style.is_some()
+    }
+
+    /// Returns `true` if both identifiers name the same object: unquoted identifiers are compared case-insensitively (Unicode lowercase mapping), and a quoted identifier only matches the exact same spelling.
+    pub fn matches(&self, other: &Ident<'_>) -> bool {
+        if self.is_quoted() || other.is_quoted() {
+            self.value == other.value
+        } else {
+            self.value == other.value || self.value.to_lowercase() == other.value.to_lowercase()
+        }
+    }
+}
+
+/// Returns the closing quote for an identifier opened with `quote`.
+pub(crate) fn closing_quote(quote: char) -> char {
+    match quote {
+        '[' => ']',
+        quote => quote,
+    }
+}
+
+impl Display for Ident<'_> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self.quote_style {
+            Some(quote) => {
+                let close = closing_quote(quote);
+                let escaped = self.value.replace(close, &format!("{}{}", close, close));
+                write!(f, "{}{}{}", quote, escaped, close)
+            },
+            None => write!(f, "{}", self.value),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_matches() {
+        assert!(Ident::new("ÄPFEL").matches(&Ident::new("äpfel")));
+        assert!(Ident::new("Users").matches(&Ident::new("users")));
+        assert!(Ident::with_quote('"', "users").matches(&Ident::new("users")));
+        assert!(!Ident::with_quote('"', "Users").matches(&Ident::new("users")));
+        assert!(!Ident::with_quote('"', "Users").matches(&Ident::with_quote('`', "USERS")));
+    }
+
+    #[test]
+    fn test_case_folding() {
+        let ident = Ident::folded("Äpfel", CaseFolding::Lower);
+        assert_eq!((ident.value.as_ref(), ident.normalized.as_ref()), ("Äpfel", "äpfel"));
+        assert_eq!(Ident::folded("straße", CaseFolding::Upper).normalized, "STRASSE");
+        assert_eq!(Ident::folded("Users", CaseFolding::Preserve), Ident::new("Users"));
+        assert_eq!(Ident::with_quote('"', "Users").normalized, "Users");
+    }
+
+    #[test]
+    fn test_display() {
+        assert_eq!(Ident::new("ąžuolas").to_string(), "ąžuolas");
+        assert_eq!(Ident::with_quote('"', "say \"hi\"").to_string(), r#""say ""hi""""#);
+        assert_eq!(Ident::with_quote('[', "a]b").to_string(), "[a]]b]");
+    }
+}
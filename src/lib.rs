@@ -0,0 +1,86 @@
+//! A SQL parser for `SELECT` and `CREATE TABLE` statements, built on a hand written tokenizer and a Pratt expression parser.
+//!
+//! The easiest way to use the crate is the [`parse_sql`] function, which turns a query string into a vector of [`Statement`]s:
+//!
+//! ```
+//! use angunna_gamage_thilina_ranmal::{parse_sql, Statement};
+//!
+//! let statements = parse_sql("SELECT name FROM users;").unwrap();
+//! assert!(matches!(statements[0], Statement::Select { .. }));
+//! ```
+//!
+//! The lower level building blocks ([`Tokenizer`], [`Parser`]) are exported as well, for callers that need more control.
+//!
+//! Tokens and the AST borrow their text from the input instead of copying it, so a `Statement<'a>` cannot outlive the string it was parsed from; `Statement::into_owned` detaches it when it has to.
+
+pub mod cst;
+pub mod diagnostic;
+pub mod dialect;
+pub mod error;
+pub mod fold;
+pub mod format;
+pub mod ident;
+pub mod parser;
+#[cfg(feature = "serde")]
+mod serialize;
+pub mod span;
+pub mod statement;
+pub mod stream;
+pub mod token;
+pub mod tokenizer;
+pub mod visitor;
+
+pub use cst::{SyntaxElement, SyntaxKind, SyntaxNode, SyntaxToken, SyntaxTree};
+pub use diagnostic::Diagnostic;
+pub use dialect::{Dialect, GenericDialect, MySqlDialect, PostgresDialect, SqliteDialect};
+pub use error::{ParseError, ParseErrorKind, ReadError};
+pub use fold::Transformed;
+pub use format::{format_sql, format_statement, FormatOptions, KeywordCase};
+pub use ident::{CaseFolding, Ident};
+pub use parser::Parser;
+pub use span::Span;
+pub use statement::{BinaryOperator, Constraint, DBType, Expression, Sql, Statement, TableColumn, UnaryOperator};
+pub use stream::StatementReader;
+pub use token::{Keyword, Token};
+pub use tokenizer::{Tokenizer, TokenizerOptions};
+pub use visitor::{Visitor, VisitorMut};
+
+/// Parses every statement in `sql` and returns them in the order they appear. Each statement must be terminated with a `;`. The input is read with the permissive `GenericDialect`; use `parse_sql_with_dialect` for a specific database.
+pub fn parse_sql(sql: &str) -> Result<Vec<Statement<'_>>, ParseError> {
+    parse_sql_with_dialect(sql, &GenericDialect)
+}
+
+/// Parses every statement in `sql` like `parse_sql`, following the quoting, escaping, keyword and operator rules of `dialect`.
+pub fn parse_sql_with_dialect<'a>(sql: &'a str, dialect: &'a dyn Dialect) -> Result<Vec<Statement<'a>>, ParseError> {
+    Parser::new(sql, dialect).parse_statements()
+}
+
+/// Parses every statement in `sql` without stopping at the first error. Returns the statements that could be parsed (including `CREATE TABLE` statements with broken column definitions left out) together with every error found. See `Parser::parse_statements_with_recovery`.
+pub fn parse_sql_with_recovery(sql: &str) -> (Vec<Statement<'_>>, Vec<ParseError>) {
+    Parser::new(sql, &GenericDialect).parse_statements_with_recovery()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_sql() {
+        let statements = parse_sql("SELECT id FROM users; SELECT name FROM users;").unwrap();
+        assert_eq!(statements.len(), 2);
+    }
+
+    #[test]
+    fn test_parse_sql_error() {
+        let error = parse_sql("SELECT id FROM users").unwrap_err();
+        assert_eq!(error.kind(), &ParseErrorKind::UnexpectedEof { expected: "';'".to_string() });
+        assert_eq!(error.statement_index(), Some(0));
+    }
+
+    #[test]
+    fn test_parse_sql_with_recovery() {
+        let (statements, errors) = parse_sql_with_recovery("SELECT id FROM; SELECT id FROM users; SELECT;");
+        assert_eq!(statements.len(), 1);
+        assert_eq!(errors.len(), 2);
+    }
+}
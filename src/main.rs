@@ -1,12 +1,53 @@
+use std::env;
+use std::fs;
 use std::io::{self, Write};
-use crate::parser::Parser;
+use std::process;
 
-mod token;
-mod tokenizer;
-mod statement;
-mod parser;
+use angunna_gamage_thilina_ranmal::dialect::dialect_from_name;
+use angunna_gamage_thilina_ranmal::{Diagnostic, Dialect, GenericDialect, Parser, Statement};
 
 fn main() -> io::Result<()> {
+    let mut dialect: Box<dyn Dialect> = Box::new(GenericDialect);
+    let mut json = false;
+    let mut paths = Vec::new();
+    let mut args = env::args().skip(1);
+    while let Some(arg) = args.next() {
+        if arg == "--json" {
+            if cfg!(not(feature = "serde")) {
+                eprintln!("--json needs the parser to be built with the serde feature (cargo run --features serde)");
+                process::exit(2);
+            }
+            json = true;
+        } else if arg == "--dialect" {
+            let name = args.next().unwrap_or_default();
+            dialect = dialect_from_name(&name).unwrap_or_else(|| {
+                eprintln!("Unknown dialect '{}', expected generic, postgres, mysql or sqlite", name);
+                process::exit(2);
+            });
+        } else {
+            paths.push(arg);
+        }
+    }
+
+    if paths.is_empty() {
+        return run_repl(dialect.as_ref(), json);
+    }
+
+    let mut failed = false;
+    for path in &paths {
+        let source = fs::read_to_string(path)?;
+        if !parse_source(&source, path, dialect.as_ref(), json) {
+            failed = true;
+        }
+    }
+
+    if failed {
+        process::exit(1);
+    }
+    Ok(())
+}
+
+fn run_repl(dialect: &dyn Dialect, json: bool) -> io::Result<()> {
     println!("Welcome to the SQL Parser!");
     println!("Enter SQL queries (press Ctrl+C to exit)");
     
@@ -15,16 +56,43 @@ fn main() -> io::Result<()> {
         io::stdout().flush()?;
         
         let mut input = String::new();
-        io::stdin().read_line(&mut input)?;
+        if io::stdin().read_line(&mut input)? == 0 {
+            return Ok(());
+        }
         
         let input = input.trim();
         if input.is_empty() {
             continue;
         }
         
-        match Parser::new(input).parse_statement() {
-            Ok(statement) => println!("{:#?}", statement),
-            Err(error) => eprintln!("Error: {}", error),
+        parse_source(input, "<stdin>", dialect, json);
+    }
+}
+
+/// Parses `source`, printing every statement that parsed (as Rust debug output, or as one line of JSON each with `json`) and a diagnostic for every error. Returns `false` if there were any errors.
+fn parse_source(source: &str, origin: &str, dialect: &dyn Dialect, json: bool) -> bool {
+    let (statements, errors) = Parser::new(source, dialect).parse_statements_with_recovery();
+
+    for statement in statements {
+        if json {
+            print_json(&statement);
+        } else {
+            println!("{:#?}", statement);
         }
     }
+    for error in &errors {
+        eprintln!("{}\n", Diagnostic::new(error, source).with_origin(origin));
+    }
+
+    errors.is_empty()
+}
+
+#[cfg(feature = "serde")]
+fn print_json(statement: &Statement<'_>) {
+    println!("{}", serde_json::to_string(statement).expect("statements always serialize"));
+}
+
+#[cfg(not(feature = "serde"))]
+fn print_json(_statement: &Statement<'_>) {
+    unreachable!("--json is rejected without the serde feature")
 }
@@ -1,97 +1,221 @@
+use crate::dialect::Dialect;
+use crate::error::{ParseError, ParseErrorKind};
+use crate::ident::Ident;
+use crate::span::Span;
 use crate::statement::{BinaryOperator, Expression, UnaryOperator, Statement, TableColumn, DBType, Constraint};
 use crate::token::{Keyword, Token};
-use crate::tokenizer::Tokenizer;
+use crate::tokenizer::{Tokenizer, TokenizerOptions};
+use std::mem;
 
 pub struct Parser<'a> {
     tokenizer: Tokenizer<'a>,
-    current_token: Token,
+    dialect: &'a dyn Dialect,
+    current_token: Token<'a>,
+    current_span: Span,
+    /// The span of the last consumed token, used to find where a node ends.
+    previous_span: Span,
+    /// A lexical error hit while reading the very first token in `new`, reported by the first parse call.
+    pending_error: Option<ParseError>,
+    /// Set by `parse_statements_with_recovery`: errors are collected in `errors` instead of aborting the parse.
+    recovering: bool,
+    errors: Vec<ParseError>,
+    /// The number of consumed `(` tokens that have not been closed yet.
+    depth: usize,
 }
 
 impl<'a> Parser<'a> {
-    pub fn new(input: &'a str) -> Self {
-        let mut tokenizer = Tokenizer::new(input);
-        let current_token = tokenizer.next().unwrap_or(Token::Eof);
-        Self {
+    /// Creates a parser for `input` written in the given SQL `dialect`, for example `&GenericDialect`.
+    pub fn new(input: &'a str, dialect: &'a dyn Dialect) -> Self {
+        Self::with_options(input, dialect, TokenizerOptions::default())
+    }
+
+    /// Creates a parser whose tokenizer uses `options`. Comments and whitespace are skipped even if `emit_comments` or `emit_whitespace` is set.
+    pub fn with_options(input: &'a str, dialect: &'a dyn Dialect, options: TokenizerOptions) -> Self {
+        Self::from_tokenizer(Tokenizer::with_options(input, dialect, options))
+    }
+
+    /// Creates a parser that reads its tokens from `tokenizer`, in the tokenizer's dialect. Useful for a tokenizer that was set up with `starting_at`.
+    pub fn from_tokenizer(tokenizer: Tokenizer<'a>) -> Self {
+        let mut parser = Self {
+            dialect: tokenizer.dialect(),
             tokenizer,
-            current_token,
+            current_token: Token::Eof,
+            current_span: Span::default(),
+            previous_span: Span::default(),
+            pending_error: None,
+            recovering: false,
+            errors: Vec::new(),
+            depth: 0,
+        };
+        parser.pending_error = parser.advance().err();
+        parser
+    }
+
+    /// Returns `true` once every token of the input has been consumed.
+    pub fn is_eof(&self) -> bool {
+        self.current_token == Token::Eof && self.pending_error.is_none()
+    }
+
+    /// Moves to the next token. When recovering, lexical errors are recorded and the offending text is skipped, so this never fails.
+    fn advance(&mut self) -> Result<(), ParseError> {
+        match self.current_token {
+            Token::LeftParentheses => self.depth += 1,
+            Token::RightParentheses => self.depth = self.depth.saturating_sub(1),
+            _ => {},
+        }
+        self.previous_span = self.current_span;
+        loop {
+            match self.tokenizer.next() {
+                Some(Ok((Token::Comment(_) | Token::Whitespace(_), _))) => continue,
+                Some(Ok((token, span))) => {
+                    self.current_token = token;
+                    self.current_span = span;
+                },
+                None => {
+                    self.current_token = Token::Eof;
+                    self.current_span = self.tokenizer.location();
+                },
+                Some(Err(error)) if self.recovering => {
+                    self.errors.push(error);
+                    continue;
+                },
+                Some(Err(error)) => return Err(error),
+            }
+            return Ok(());
+        }
+    }
+
+    /// Returns the span from `start` up to the end of the last consumed token.
+    fn span_from(&self, start: Span) -> Span {
+        start.union(&self.previous_span)
+    }
+
+    fn check_pending_error(&mut self) -> Result<(), ParseError> {
+        match self.pending_error.take() {
+            Some(error) => Err(error),
+            None => Ok(()),
         }
     }
 
-    fn advance(&mut self) {
-        self.current_token = self.tokenizer.next().unwrap_or(Token::Eof);
+    fn unexpected<T>(&self, expected: &str) -> Result<T, ParseError> {
+        Err(ParseError::unexpected(expected, self.current_token.clone(), self.current_span))
     }
 
-    fn expect_token(&mut self, expected: Token) -> Result<(), String> {
+    /// Moves the current token out of the parser without copying its text, leaving `Token::Eof` in its place. The caller must `advance` before looking at the current token again.
+    fn take_token(&mut self) -> Token<'a> {
+        mem::replace(&mut self.current_token, Token::Eof)
+    }
+
+    fn expect_token(&mut self, expected: Token) -> Result<(), ParseError> {
         if self.current_token == expected {
-            self.advance();
-            Ok(())
+            self.advance()
         } else {
-            Err(format!("Expected {:?}, got {:?}", expected, self.current_token))
+            self.unexpected(&format!("'{}'", expected))
+        }
+    }
+
+    /// Returns the current token as an identifier: an `Identifier` token, or a keyword the dialect does not reserve, spelled as in the input (so a column can be named `key` or `Check`). An identifier is taken out of the current token (see `take_token`), so the caller must `advance` when this returns `Some`.
+    fn identifier_token(&mut self) -> Option<Ident<'a>> {
+        match &self.current_token {
+            Token::Identifier(_) => match self.take_token() {
+                Token::Identifier(ident) => Some(ident),
+                _ => unreachable!("the current token is an identifier"),
+            },
+            Token::Keyword(keyword) if !self.dialect.is_reserved_keyword(keyword) => {
+                Some(Ident::folded(self.tokenizer.text(self.current_span), self.tokenizer.case_folding()))
+            },
+            _ => None,
         }
     }
 
-    fn get_precedence(operator: &BinaryOperator) -> u8 {
-        match operator {
-            BinaryOperator::Or => 1,
-            BinaryOperator::And => 2,
-            BinaryOperator::Equal | BinaryOperator::NotEqual => 3,
-            BinaryOperator::GreaterThan | BinaryOperator::GreaterThanOrEqual |
-            BinaryOperator::LessThan | BinaryOperator::LessThanOrEqual => 4,
-            BinaryOperator::Plus | BinaryOperator::Minus => 5,
-            BinaryOperator::Multiply | BinaryOperator::Divide => 6,
+    fn parse_identifier(&mut self, expected: &str) -> Result<Ident<'a>, ParseError> {
+        match self.identifier_token() {
+            Some(ident) => {
+                self.advance()?;
+                Ok(ident)
+            },
+            None => self.unexpected(expected),
         }
     }
 
-    fn parse_primary(&mut self) -> Result<Expression, String> {
-        let token = self.current_token.clone();
-        self.advance();
+    fn expect_keyword(&mut self, expected: Keyword) -> Result<(), ParseError> {
+        if self.current_token == Token::Keyword(expected.clone()) {
+            self.advance()
+        } else {
+            self.unexpected(&expected.to_string())
+        }
+    }
 
-        match token {
-            Token::Number(n) => Ok(Expression::Number(n)),
-            Token::String(s) => Ok(Expression::String(s)),
-            Token::Identifier(id) => Ok(Expression::Identifier(id)),
-            Token::Keyword(Keyword::True) => Ok(Expression::Bool(true)),
-            Token::Keyword(Keyword::False) => Ok(Expression::Bool(false)),
-            Token::Star => Ok(Expression::Identifier("*".to_string())),
-            Token::LeftParentheses => {
-                let expr = self.parse_expression(0)?;
-                if self.current_token != Token::RightParentheses {
-                    return Err("Expected closing parenthesis".to_string());
+    /// Parses a literal, an identifier, a parenthesized expression or a unary operation. Literals and identifiers are moved out of the current token, so their text is never copied.
+    fn parse_primary(&mut self) -> Result<Expression<'a>, ParseError> {
+        let span = self.current_span;
+
+        let expr = match self.take_token() {
+            Token::Number(n) => Expression::Number(n, span),
+            Token::Decimal(d) => Expression::Decimal(d, span),
+            Token::HexString(digits) => Expression::HexString(digits, span),
+            Token::BitString(digits) => Expression::BitString(digits, span),
+            Token::String(s) => Expression::String(s, span),
+            Token::Identifier(id) => Expression::Identifier(id, span),
+            Token::Keyword(Keyword::True) => Expression::Bool(true, span),
+            Token::Keyword(Keyword::False) => Expression::Bool(false, span),
+            Token::Star => Expression::Identifier(Ident::new("*"), span),
+            token => {
+                self.current_token = token;
+                match self.current_token {
+                    Token::LeftParentheses => {
+                        self.advance()?;
+                        let expr = self.parse_expression(0)?;
+                        self.expect_token(Token::RightParentheses)
+                            .map_err(|error| error.with_hint("every '(' needs a matching ')'"))?;
+                        return Ok(expr);
+                    },
+                    Token::Minus => return self.parse_unary_operation(UnaryOperator::Minus),
+                    Token::Plus => return self.parse_unary_operation(UnaryOperator::Plus),
+                    Token::Keyword(Keyword::Not) => return self.parse_unary_operation(UnaryOperator::Not),
+                    _ => match self.identifier_token() {
+                        Some(ident) => Expression::Identifier(ident, span),
+                        None => return self.unexpected("an expression"),
+                    },
                 }
-                self.advance();
-                Ok(expr)
-            },
-            Token::Minus => {
-                let expr = self.parse_expression(7)?;
-                Ok(Expression::UnaryOperation {
-                    operand: Box::new(expr),
-                    operator: UnaryOperator::Minus,
-                })
-            },
-            Token::Plus => {
-                let expr = self.parse_expression(7)?;
-                Ok(Expression::UnaryOperation {
-                    operand: Box::new(expr),
-                    operator: UnaryOperator::Plus,
-                })
-            },
-            Token::Keyword(Keyword::Not) => {
-                let expr = self.parse_expression(7)?;
-                Ok(Expression::UnaryOperation {
-                    operand: Box::new(expr),
-                    operator: UnaryOperator::Not,
-                })
             },
-            _ => Err(format!("Unexpected token: {:?}", token)),
-        }
+        };
+
+        self.advance()?;
+        Ok(expr)
+    }
+
+    fn parse_unary_operation(&mut self, operator: UnaryOperator) -> Result<Expression<'a>, ParseError> {
+        let start = self.current_span;
+        self.advance()?;
+        let expr = self.parse_expression(operator.precedence())?;
+        Ok(Expression::UnaryOperation {
+            operand: Box::new(expr),
+            operator,
+            span: self.span_from(start),
+        })
+    }
+
+    /// Parses the `::type` suffix of a PostgreSQL style cast applied to `expression`, which started at `start`. The cast binds tighter than any other operator, so `-1::INT` casts `1`.
+    fn parse_cast(&mut self, expression: Expression<'a>, start: Span) -> Result<Expression<'a>, ParseError> {
+        self.advance()?;
+        let data_type = self.parse_column_type()?;
+        Ok(Expression::Cast {
+            span: self.span_from(start),
+            expression: Box::new(expression),
+            data_type,
+        })
     }
 
-    fn get_binary_operator(token: &Token) -> Option<BinaryOperator> {
+    fn get_binary_operator(&self, token: &Token) -> Option<BinaryOperator> {
         match token {
             Token::Plus => Some(BinaryOperator::Plus),
             Token::Minus => Some(BinaryOperator::Minus),
             Token::Star => Some(BinaryOperator::Multiply),
             Token::Divide => Some(BinaryOperator::Divide),
+            Token::Percent => Some(BinaryOperator::Modulo),
+            Token::DoublePipe if self.dialect.pipes_as_concat() => Some(BinaryOperator::Concat),
+            Token::DoublePipe => Some(BinaryOperator::Or),
             Token::GreaterThan => Some(BinaryOperator::GreaterThan),
             Token::GreaterThanOrEqual => Some(BinaryOperator::GreaterThanOrEqual),
             Token::LessThan => Some(BinaryOperator::LessThan),
@@ -104,36 +228,49 @@ impl<'a> Parser<'a> {
         }
     }
 
-    pub fn parse_expression(&mut self, precedence: u8) -> Result<Expression, String> {
+    /// Parses an expression whose operators all bind tighter than `precedence`. Operations and casts span from the first token of their left operand to the last token of their right operand, so an operand in parentheses is covered together with its parentheses: the span of `(a + b) * c` is the whole text, while the parenthesized `a + b` on its own spans `a + b`.
+    pub fn parse_expression(&mut self, precedence: u8) -> Result<Expression<'a>, ParseError> {
+        self.check_pending_error()?;
+        let start = self.current_span;
         let mut left = self.parse_primary()?;
 
-        while let Some(operator) = Self::get_binary_operator(&self.current_token) {
-            let op_precedence = Self::get_precedence(&operator);
+        loop {
+            if self.current_token == Token::DoubleColon {
+                left = self.parse_cast(left, start)?;
+                continue;
+            }
+
+            let Some(operator) = self.get_binary_operator(&self.current_token) else {
+                break;
+            };
+            let op_precedence = operator.precedence();
             if op_precedence <= precedence {
                 break;
             }
 
-            self.advance();
+            self.advance()?;
             let right = self.parse_expression(op_precedence)?;
+            let span = self.span_from(start);
 
             left = Expression::BinaryOperation {
                 left_operand: Box::new(left),
                 operator,
                 right_operand: Box::new(right),
+                span,
             };
         }
 
         Ok(left)
     }
 
-    fn parse_select_columns(&mut self) -> Result<Vec<Expression>, String> {
+    fn parse_select_columns(&mut self) -> Result<Vec<Expression<'a>>, ParseError> {
         let mut columns = Vec::new();
         
         loop {
             columns.push(self.parse_expression(0)?);
             
             if self.current_token == Token::Comma {
-                self.advance();
+                self.advance()?;
             } else {
                 break;
             }
@@ -142,35 +279,34 @@ impl<'a> Parser<'a> {
         Ok(columns)
     }
 
-    fn parse_orderby(&mut self) -> Result<Vec<Expression>, String> {
+    fn parse_orderby(&mut self) -> Result<Vec<Expression<'a>>, ParseError> {
         let mut orderby = Vec::new();
         
         loop {
             let expr = self.parse_expression(0)?;
             
             // Check for ASC/DESC
-            let expr = match self.current_token {
-                Token::Keyword(Keyword::Asc) => {
-                    self.advance();
-                    Expression::UnaryOperation {
-                        operand: Box::new(expr),
-                        operator: UnaryOperator::Asc,
-                    }
-                },
-                Token::Keyword(Keyword::Desc) => {
-                    self.advance();
+            let operator = match self.current_token {
+                Token::Keyword(Keyword::Asc) => Some(UnaryOperator::Asc),
+                Token::Keyword(Keyword::Desc) => Some(UnaryOperator::Desc),
+                _ => None,
+            };
+            let expr = match operator {
+                Some(operator) => {
+                    self.advance()?;
                     Expression::UnaryOperation {
+                        span: self.span_from(expr.span()),
                         operand: Box::new(expr),
-                        operator: UnaryOperator::Desc,
+                        operator,
                     }
                 },
-                _ => expr,
+                None => expr,
             };
             
             orderby.push(expr);
             
             if self.current_token == Token::Comma {
-                self.advance();
+                self.advance()?;
             } else {
                 break;
             }
@@ -179,60 +315,65 @@ impl<'a> Parser<'a> {
         Ok(orderby)
     }
 
-    fn parse_column_type(&mut self) -> Result<DBType, String> {
+    fn parse_column_type(&mut self) -> Result<DBType, ParseError> {
         match self.current_token {
             Token::Keyword(Keyword::Int) => {
-                self.advance();
+                self.advance()?;
                 Ok(DBType::Int)
             },
             Token::Keyword(Keyword::Bool) => {
-                self.advance();
+                self.advance()?;
                 Ok(DBType::Bool)
             },
+            Token::Keyword(Keyword::Blob | Keyword::Bytea) => {
+                self.advance()?;
+                Ok(DBType::Blob)
+            },
             Token::Keyword(Keyword::Varchar) => {
-                self.advance();
+                self.advance()?;
                 self.expect_token(Token::LeftParentheses)?;
                 
-                if let Token::Number(size) = self.current_token {
-                    self.advance();
-                    self.expect_token(Token::RightParentheses)?;
-                    Ok(DBType::Varchar(size as usize))
-                } else {
-                    Err("Expected number for VARCHAR size".to_string())
-                }
+                let size = match &self.current_token {
+                    Token::Number(size) => usize::try_from(*size).ok(),
+                    Token::Decimal(number) if number.bytes().all(|b| b.is_ascii_digit()) => None,
+                    _ => return self.unexpected("a number for VARCHAR size"),
+                };
+                let Some(size) = size else {
+                    let number = self.current_token.to_string();
+                    return Err(ParseError::new(ParseErrorKind::NumberOutOfRange(number), self.current_span));
+                };
+                self.advance()?;
+                self.expect_token(Token::RightParentheses)?;
+                Ok(DBType::Varchar(size))
             },
-            _ => Err("Expected a valid data type".to_string()),
+            _ => self.unexpected("a data type")
+                .map_err(|error| error.with_hint("supported data types are INT, VARCHAR(n), BOOL and BLOB (or BYTEA)")),
         }
     }
 
-    fn parse_column_constraints(&mut self) -> Result<Vec<Constraint>, String> {
+    fn parse_column_constraints(&mut self) -> Result<Vec<Constraint<'a>>, ParseError> {
         let mut constraints = Vec::new();
         
         while let Some(constraint) = match self.current_token {
             Token::Keyword(Keyword::Primary) => {
-                self.advance();
-                if let Token::Keyword(Keyword::Key) = self.current_token {
-                    self.advance();
-                    Some(Constraint::PrimaryKey)
-                } else {
-                    return Err("Expected KEY after PRIMARY".to_string());
-                }
+                let start = self.current_span;
+                self.advance()?;
+                self.expect_keyword(Keyword::Key)?;
+                Some(Constraint::PrimaryKey(self.span_from(start)))
             },
             Token::Keyword(Keyword::Not) => {
-                self.advance();
-                if let Token::Keyword(Keyword::Null) = self.current_token {
-                    self.advance();
-                    Some(Constraint::NotNull)
-                } else {
-                    return Err("Expected NULL after NOT".to_string());
-                }
+                let start = self.current_span;
+                self.advance()?;
+                self.expect_keyword(Keyword::Null)?;
+                Some(Constraint::NotNull(self.span_from(start)))
             },
             Token::Keyword(Keyword::Check) => {
-                self.advance();
+                let start = self.current_span;
+                self.advance()?;
                 self.expect_token(Token::LeftParentheses)?;
                 let expr = self.parse_expression(0)?;
                 self.expect_token(Token::RightParentheses)?;
-                Some(Constraint::Check(expr))
+                Some(Constraint::Check(expr, self.span_from(start)))
             },
             _ => None,
         } {
@@ -242,176 +383,738 @@ impl<'a> Parser<'a> {
         Ok(constraints)
     }
 
-    fn parse_column_definition(&mut self) -> Result<TableColumn, String> {
-        if let Token::Identifier(name) = self.current_token.clone() {
-            self.advance();
-            let column_type = self.parse_column_type()?;
-            let constraints = self.parse_column_constraints()?;
-            
-            Ok(TableColumn {
-                column_name: name,
-                column_type,
-                constraints,
-            })
-        } else {
-            Err("Expected column name".to_string())
+    fn parse_column_definition(&mut self) -> Result<TableColumn<'a>, ParseError> {
+        let start = self.current_span;
+        let column_name = self.parse_identifier("a column name")?;
+        let column_type = self.parse_column_type()?;
+        let constraints = self.parse_column_constraints()?;
+
+        Ok(TableColumn {
+            column_name,
+            column_type,
+            constraints,
+            span: self.span_from(start),
+        })
+    }
+
+    /// Consumes the `,` or `)` after a column definition. Returns `true` if another column definition follows.
+    fn parse_column_separator(&mut self) -> Result<bool, ParseError> {
+        match self.current_token {
+            Token::Comma => {
+                self.advance()?;
+                Ok(true)
+            },
+            Token::RightParentheses => {
+                self.advance()?;
+                Ok(false)
+            },
+            _ => self.unexpected("',' or ')'")
+                .map_err(|error| error.with_hint("column definitions are separated by ',' and the list is closed with ')'")),
+        }
+    }
+
+    /// Skips the rest of a broken column definition, up to and including the next `,` or `)` of the column list itself (`list_depth` is the parenthesis depth inside the list). Returns `Some(true)` if another column definition follows and `Some(false)` if the list was closed. Stops without consuming anything at `;` or the end of the input and returns `None`, since the list was never closed.
+    fn skip_column_definition(&mut self, list_depth: usize) -> Result<Option<bool>, ParseError> {
+        loop {
+            match self.current_token {
+                Token::Comma if self.depth == list_depth => {
+                    self.advance()?;
+                    return Ok(Some(true));
+                },
+                Token::RightParentheses if self.depth == list_depth => {
+                    self.advance()?;
+                    return Ok(Some(false));
+                },
+                Token::Semicolon | Token::Eof => return Ok(None),
+                _ => self.advance()?,
+            }
         }
     }
 
-    fn parse_create_table(&mut self) -> Result<Statement, String> {
-        self.advance(); // Skip TABLE keyword
+    fn parse_create_table(&mut self, start: Span) -> Result<Statement<'a>, ParseError> {
+        self.expect_keyword(Keyword::Table)?;
         
-        let table_name = if let Token::Identifier(name) = self.current_token.clone() {
-            self.advance();
-            name
-        } else {
-            return Err("Expected table name".to_string());
-        };
+        let table_name = self.parse_identifier("a table name")?;
         
         self.expect_token(Token::LeftParentheses)?;
         
         let mut column_list = Vec::new();
+        let list_depth = self.depth;
         
         loop {
-            column_list.push(self.parse_column_definition()?);
-            
-            match self.current_token {
-                Token::Comma => {
-                    self.advance();
-                    continue;
-                },
-                Token::RightParentheses => {
-                    self.advance();
-                    break;
+            let result = self.parse_column_definition().and_then(|column| {
+                column_list.push(column);
+                self.parse_column_separator()
+            });
+
+            match result {
+                Ok(true) => continue,
+                Ok(false) => break,
+                Err(error) if self.recovering => {
+                    self.errors.push(error);
+                    match self.skip_column_definition(list_depth)? {
+                        Some(true) => continue,
+                        Some(false) => break,
+                        // The error already reported the missing ')', so a missing ';' is not reported again.
+                        None => return Ok(Statement::CreateTable {
+                            table_name,
+                            column_list,
+                            span: self.span_from(start),
+                        }),
+                    }
                 },
-                _ => return Err("Expected ',' or ')'".to_string()),
+                Err(error) => return Err(error),
             }
         }
         
-        self.expect_token(Token::Semicolon)?;
+        self.expect_token(Token::Semicolon)
+            .map_err(|error| error.with_hint("CREATE TABLE statements must end with ';'"))?;
         
         Ok(Statement::CreateTable {
             table_name,
             column_list,
+            span: self.span_from(start),
         })
     }
 
-    fn parse_select(&mut self) -> Result<Statement, String> {
+    fn parse_select(&mut self, start: Span) -> Result<Statement<'a>, ParseError> {
         let columns = self.parse_select_columns()?;
         
-        if self.current_token != Token::Keyword(Keyword::From) {
-            return Err("Expected FROM clause".to_string());
-        }
-        self.advance();
+        self.expect_keyword(Keyword::From)
+            .map_err(|error| error.with_hint("SELECT statements must name a table in a FROM clause"))?;
         
-        let from = if let Token::Identifier(table_name) = self.current_token.clone() {
-            self.advance();
-            table_name
-        } else {
-            return Err("Expected table name".to_string());
-        };
+        let from = self.parse_identifier("a table name")?;
         
         let mut r#where = None;
         let mut orderby = Vec::new();
         
         if self.current_token == Token::Keyword(Keyword::Where) {
-            self.advance();
+            self.advance()?;
             r#where = Some(self.parse_expression(0)?);
         }
         
         if self.current_token == Token::Keyword(Keyword::Order) {
-            self.advance();
-            if self.current_token != Token::Keyword(Keyword::By) {
-                return Err("Expected BY after ORDER".to_string());
-            }
-            self.advance();
+            self.advance()?;
+            self.expect_keyword(Keyword::By)?;
             orderby = self.parse_orderby()?;
         }
         
-        self.expect_token(Token::Semicolon)?;
+        self.expect_token(Token::Semicolon)
+            .map_err(|error| error.with_hint("SELECT statements must end with ';'"))?;
         
         Ok(Statement::Select {
             columns,
             from,
             r#where,
             orderby,
+            span: self.span_from(start),
         })
     }
 
-    pub fn parse_statement(&mut self) -> Result<Statement, String> {
-        match self.current_token {
+    pub fn parse_statement(&mut self) -> Result<Statement<'a>, ParseError> {
+        self.check_pending_error()?;
+        let start = self.current_span;
+        self.depth = 0;
+
+        match &self.current_token {
+            Token::Keyword(keyword @ (Keyword::Select | Keyword::Create)) if !self.dialect.supports_statement(keyword) => {
+                self.unexpected("a statement supported by this dialect")
+            },
             Token::Keyword(Keyword::Select) => {
-                self.advance();
-                self.parse_select()
+                self.advance()?;
+                self.parse_select(start)
             },
             Token::Keyword(Keyword::Create) => {
-                self.advance();
-                if self.current_token != Token::Keyword(Keyword::Table) {
-                    return Err("Expected TABLE after CREATE".to_string());
-                }
-                self.parse_create_table()
+                self.advance()?;
+                self.parse_create_table(start)
             },
-            _ => Err("Expected SELECT or CREATE".to_string()),
+            _ => self.unexpected("SELECT or CREATE")
+                .map_err(|error| error.with_hint("only SELECT and CREATE TABLE statements are supported")),
+        }
+    }
+
+    /// Parses a whole script of semicolon separated statements until the end of the input. Empty statements (a `;` with nothing before it) are skipped. If a statement fails to parse, the returned error records its index in the script.
+    pub fn parse_statements(&mut self) -> Result<Vec<Statement<'a>>, ParseError> {
+        let mut statements = Vec::new();
+
+        loop {
+            let index = statements.len();
+            let result = self.skip_empty_statements().and_then(|_| {
+                if self.is_eof() {
+                    Ok(None)
+                } else {
+                    self.parse_statement().map(Some)
+                }
+            });
+
+            match result {
+                Ok(Some(statement)) => statements.push(statement),
+                Ok(None) => break,
+                Err(error) => return Err(error.with_statement_index(index)),
+            }
+        }
+
+        Ok(statements)
+    }
+
+    /// Parses a whole script like `parse_statements`, but does not stop at the first error. After an error the parser skips to the next `;` and carries on with the next statement; inside `CREATE TABLE (...)` it skips only the broken column definition, so the statement is still returned with the columns that parsed. Returns the statements that could be parsed together with every error found, each tagged with the index of its statement.
+    pub fn parse_statements_with_recovery(&mut self) -> (Vec<Statement<'a>>, Vec<ParseError>) {
+        self.recovering = true;
+        let mut statements = Vec::new();
+
+        if let Some(error) = self.pending_error.take() {
+            self.errors.push(error);
+            self.skip_token();
+        }
+
+        let mut index = 0;
+        loop {
+            while self.current_token == Token::Semicolon {
+                self.skip_token();
+            }
+            if self.is_eof() {
+                break;
+            }
+
+            let first_error = self.errors.len();
+            match self.parse_statement() {
+                Ok(statement) => statements.push(statement),
+                Err(error) => {
+                    self.errors.push(error);
+                    self.skip_statement();
+                },
+            }
+
+            let tagged: Vec<ParseError> = self.errors
+                .drain(first_error..)
+                .map(|error| error.with_statement_index(index))
+                .collect();
+            self.errors.extend(tagged);
+            index += 1;
+        }
+
+        self.recovering = false;
+        (statements, std::mem::take(&mut self.errors))
+    }
+
+    /// Advances in recovery mode, where `advance` records lexical errors instead of returning them.
+    fn skip_token(&mut self) {
+        if let Err(error) = self.advance() {
+            self.errors.push(error);
+        }
+    }
+
+    /// Skips the rest of a broken statement, up to and including the next `;`.
+    fn skip_statement(&mut self) {
+        while self.current_token != Token::Semicolon && self.current_token != Token::Eof {
+            self.skip_token();
+        }
+        self.skip_token();
+    }
+
+    fn skip_empty_statements(&mut self) -> Result<(), ParseError> {
+        self.check_pending_error()?;
+        while self.current_token == Token::Semicolon {
+            self.advance()?;
         }
+        Ok(())
     }
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::dialect::{GenericDialect, MySqlDialect, PostgresDialect, SqliteDialect};
+    use crate::ident::CaseFolding;
+    use std::borrow::Cow;
+
+    fn identifier(name: &str) -> Expression<'_> {
+        Expression::Identifier(Ident::new(name), Span::default())
+    }
+
+    fn number(n: u64) -> Expression<'static> {
+        Expression::Number(n, Span::default())
+    }
+
+    fn binary<'a>(left: Expression<'a>, operator: BinaryOperator, right: Expression<'a>) -> Expression<'a> {
+        Expression::BinaryOperation {
+            left_operand: Box::new(left),
+            operator,
+            right_operand: Box::new(right),
+            span: Span::default(),
+        }
+    }
 
     #[test]
     fn test_simple_select() {
-        let mut parser = Parser::new("SELECT name, age FROM users;");
+        let mut parser = Parser::new("SELECT name, age FROM users;", &GenericDialect);
         let stmt = parser.parse_statement().unwrap();
         
         assert_eq!(stmt, Statement::Select {
             columns: vec![
-                Expression::Identifier("name".to_string()),
-                Expression::Identifier("age".to_string()),
+                identifier("name"),
+                identifier("age"),
             ],
-            from: "users".to_string(),
+            from: Ident::new("users"),
             r#where: None,
             orderby: vec![],
+            span: Span::default(),
         });
     }
 
     #[test]
     fn test_select_with_where() {
-        let mut parser = Parser::new("SELECT id FROM users WHERE age >= 18;");
+        let mut parser = Parser::new("SELECT id FROM users WHERE age >= 18;", &GenericDialect);
         let stmt = parser.parse_statement().unwrap();
         
         assert_eq!(stmt, Statement::Select {
-            columns: vec![Expression::Identifier("id".to_string())],
-            from: "users".to_string(),
-            r#where: Some(Expression::BinaryOperation {
-                left_operand: Box::new(Expression::Identifier("age".to_string())),
-                operator: BinaryOperator::GreaterThanOrEqual,
-                right_operand: Box::new(Expression::Number(18)),
-            }),
+            columns: vec![identifier("id")],
+            from: Ident::new("users"),
+            r#where: Some(binary(identifier("age"), BinaryOperator::GreaterThanOrEqual, number(18))),
             orderby: vec![],
+            span: Span::default(),
         });
     }
 
     #[test]
     fn test_create_table() {
-        let mut parser = Parser::new("CREATE TABLE users(id INT PRIMARY KEY, name VARCHAR(255) NOT NULL);");
+        let mut parser = Parser::new("CREATE TABLE users(id INT PRIMARY KEY, name VARCHAR(255) NOT NULL);", &GenericDialect);
         let stmt = parser.parse_statement().unwrap();
         
         assert_eq!(stmt, Statement::CreateTable {
-            table_name: "users".to_string(),
+            table_name: Ident::new("users"),
             column_list: vec![
                 TableColumn {
-                    column_name: "id".to_string(),
+                    column_name: Ident::new("id"),
                     column_type: DBType::Int,
-                    constraints: vec![Constraint::PrimaryKey],
+                    constraints: vec![Constraint::PrimaryKey(Span::default())],
+                    span: Span::default(),
                 },
                 TableColumn {
-                    column_name: "name".to_string(),
+                    column_name: Ident::new("name"),
                     column_type: DBType::Varchar(255),
-                    constraints: vec![Constraint::NotNull],
+                    constraints: vec![Constraint::NotNull(Span::default())],
+                    span: Span::default(),
                 },
             ],
+            span: Span::default(),
+        });
+    }
+
+    #[test]
+    fn test_parse_statements() {
+        let mut parser = Parser::new("CREATE TABLE a(id INT); ;; SELECT id FROM a;;", &GenericDialect);
+        let statements = parser.parse_statements().unwrap();
+
+        assert_eq!(statements.len(), 2);
+        assert!(matches!(statements[0], Statement::CreateTable { .. }));
+        assert!(matches!(statements[1], Statement::Select { .. }));
+    }
+
+    #[test]
+    fn test_parse_statements_reports_index() {
+        let mut parser = Parser::new("SELECT id FROM a; ; SELECT FROM a;", &GenericDialect);
+        let error = parser.parse_statements().unwrap_err();
+
+        assert_eq!(error.statement_index(), Some(1));
+        assert_eq!(error.span().range(), 27..31);
+    }
+
+    #[test]
+    fn test_syntax_error() {
+        let mut parser = Parser::new("CREATE TABLE users(id INT, name TEXT);", &GenericDialect);
+        let error = parser.parse_statement().unwrap_err();
+
+        assert!(error.is_syntax());
+        assert_eq!(error.kind(), &ParseErrorKind::UnexpectedToken {
+            expected: "a data type".to_string(),
+            found: Token::Identifier(Ident::new("TEXT")),
+        });
+        assert_eq!(error.to_string(), "Expected a data type, found identifier \"TEXT\" at line 1, column 33");
+        assert_eq!(error.span().range(), 32..36);
+    }
+
+    #[test]
+    fn test_lexical_error() {
+        let mut parser = Parser::new("SELECT name FROM users WHERE name = 'Riddle;", &GenericDialect);
+        let error = parser.parse_statement().unwrap_err();
+
+        assert!(error.is_lexical());
+        assert_eq!(error.kind(), &ParseErrorKind::UnterminatedString);
+
+        let error = Parser::new("# comment", &GenericDialect).parse_statements().unwrap_err();
+        assert_eq!(error.kind(), &ParseErrorKind::InvalidCharacter('#'));
+        assert_eq!(error.statement_index(), Some(0));
+    }
+
+    #[test]
+    fn test_node_spans() {
+        let input = "SELECT id FROM users\nWHERE -age >= 18 ORDER BY id DESC;\nCREATE TABLE t(age INT CHECK(age > 0) NOT NULL);";
+        let statements = Parser::new(input, &GenericDialect).parse_statements().unwrap();
+        let text = |span: Span| &input[span.range()];
+
+        assert_eq!(text(statements[0].span()), "SELECT id FROM users\nWHERE -age >= 18 ORDER BY id DESC;");
+        if let Statement::Select { r#where: Some(r#where), orderby, .. } = &statements[0] {
+            assert_eq!(text(r#where.span()), "-age >= 18");
+            assert_eq!(r#where.span().line, 2);
+            assert_eq!(r#where.span().column, 7);
+            assert_eq!(text(orderby[0].span()), "id DESC");
+        } else {
+            panic!("expected a SELECT statement with a WHERE clause");
+        }
+
+        assert_eq!(statements[1].span().line, 3);
+        if let Statement::CreateTable { column_list, .. } = &statements[1] {
+            assert_eq!(text(column_list[0].span), "age INT CHECK(age > 0) NOT NULL");
+            assert_eq!(text(column_list[0].constraints[0].span()), "CHECK(age > 0)");
+            assert_eq!(text(column_list[0].constraints[1].span()), "NOT NULL");
+        } else {
+            panic!("expected a CREATE TABLE statement");
+        }
+    }
+
+    #[test]
+    fn test_parenthesized_operand_spans() {
+        let input = "SELECT (a + b) * c, (a)::INT, -(a), a * (b + c) FROM t;\nCREATE TABLE t(a INT CHECK ((a + 1) > 0));";
+        let statements = Parser::new(input, &GenericDialect).parse_statements().unwrap();
+        let text = |span: Span| &input[span.range()];
+
+        let Statement::Select { columns, .. } = &statements[0] else {
+            panic!("expected a SELECT statement");
+        };
+        let spans: Vec<_> = columns.iter().map(|column| text(column.span())).collect();
+        assert_eq!(spans, vec!["(a + b) * c", "(a)::INT", "-(a)", "a * (b + c)"]);
+        if let Expression::BinaryOperation { left_operand, .. } = &columns[0] {
+            assert_eq!(text(left_operand.span()), "a + b");
+        }
+
+        let Statement::CreateTable { column_list, .. } = &statements[1] else {
+            panic!("expected a CREATE TABLE statement");
+        };
+        let Constraint::Check(check, _) = &column_list[0].constraints[0] else {
+            panic!("expected a CHECK constraint");
+        };
+        assert_eq!(text(check.span()), "(a + 1) > 0");
+    }
+
+    #[test]
+    fn test_recovery_reports_every_error() {
+        let input = "SELECT FROM users;\nSELECT id FROM users;\nSELECT id users WHERE;\nSELECT # FROM t;";
+        let (statements, errors) = Parser::new(input, &GenericDialect).parse_statements_with_recovery();
+
+        assert_eq!(statements.len(), 1);
+        let found: Vec<_> = errors.iter().map(|error| (error.statement_index(), error.span().line)).collect();
+        assert_eq!(found, vec![(Some(0), 1), (Some(2), 3), (Some(3), 4), (Some(3), 4)]);
+        assert_eq!(errors[2].kind(), &ParseErrorKind::InvalidCharacter('#'));
+    }
+
+    #[test]
+    fn test_recovery_inside_create_table() {
+        let input = "CREATE TABLE t(id INT, name TEXT, age INT CHECK(age >), email VARCHAR(255));";
+        let (statements, errors) = Parser::new(input, &GenericDialect).parse_statements_with_recovery();
+
+        assert_eq!(errors.len(), 2);
+        assert_eq!(&input[errors[0].span().range()], "TEXT");
+        assert_eq!(&input[errors[1].span().range()], ")");
+        if let Statement::CreateTable { column_list, .. } = &statements[0] {
+            let names: Vec<_> = column_list.iter().map(|column| column.column_name.value.as_ref()).collect();
+            assert_eq!(names, vec!["id", "email"]);
+        } else {
+            panic!("expected a CREATE TABLE statement");
+        }
+    }
+
+    #[test]
+    fn test_recovery_in_truncated_create_table() {
+        let input = "CREATE TABLE t (a INT, b INT";
+        let (statements, errors) = Parser::new(input, &GenericDialect).parse_statements_with_recovery();
+
+        assert_eq!(errors.len(), 1);
+        assert_eq!(errors[0].kind(), &ParseErrorKind::UnexpectedEof { expected: "',' or ')'".to_string() });
+        assert_eq!(errors[0].statement_index(), Some(0));
+        assert_eq!(statements.len(), 1);
+
+        let input = "CREATE TABLE t (a INT, b INT NOT; SELECT a FROM t;";
+        let (statements, errors) = Parser::new(input, &GenericDialect).parse_statements_with_recovery();
+        let found: Vec<_> = errors.iter().map(|error| (error.statement_index(), &input[error.span().range()])).collect();
+        assert_eq!(found, vec![(Some(0), ";")]);
+        assert_eq!(statements.len(), 2);
+    }
+
+    #[test]
+    fn test_comments_are_skipped() {
+        let input = "-- schema\nCREATE TABLE t(\n    id INT, -- primary\n    /* name VARCHAR(10), */ age INT\n);";
+        let statements = Parser::new(input, &GenericDialect).parse_statements().unwrap();
+
+        if let Statement::CreateTable { column_list, .. } = &statements[0] {
+            assert_eq!(column_list.len(), 2);
+            assert_eq!(column_list[1].column_name, Ident::new("age"));
+        } else {
+            panic!("expected a CREATE TABLE statement");
+        }
+    }
+
+    #[test]
+    fn test_string_escapes() {
+        let options = TokenizerOptions { emit_comments: true, ..Default::default() };
+        let input = r"SELECT 'O''Brien', 'it\'s' /* names */ FROM users;";
+        let statement = Parser::with_options(input, &MySqlDialect, options).parse_statement().unwrap();
+
+        if let Statement::Select { columns, .. } = statement {
+            assert_eq!(columns, vec![
+                Expression::String("O'Brien".into(), Span::default()),
+                Expression::String("it's".into(), Span::default()),
+            ]);
+        } else {
+            panic!("expected a SELECT statement");
+        }
+    }
+
+    #[test]
+    fn test_quoted_identifiers() {
+        let input = r#"SELECT "order", [my column] FROM "my table";"#;
+        let statement = Parser::new(input, &SqliteDialect).parse_statement().unwrap();
+
+        assert_eq!(statement, Statement::Select {
+            columns: vec![
+                Expression::Identifier(Ident::with_quote('"', "order"), Span::default()),
+                Expression::Identifier(Ident::with_quote('[', "my column"), Span::default()),
+            ],
+            from: Ident::with_quote('"', "my table"),
+            r#where: None,
+            orderby: vec![],
+            span: Span::default(),
+        });
+    }
+
+    #[test]
+    fn test_decimal_literals() {
+        let statement = Parser::new("SELECT id FROM items WHERE price > 9.99 AND weight < -1.5e3;", &GenericDialect).parse_statement().unwrap();
+        let decimal = |text: &'static str| Expression::Decimal(text.into(), Span::default());
+
+        let Statement::Select { r#where: Some(condition), .. } = statement else {
+            panic!("expected a SELECT statement with a WHERE clause");
+        };
+        assert_eq!(condition, binary(
+            binary(identifier("price"), BinaryOperator::GreaterThan, decimal("9.99")),
+            BinaryOperator::And,
+            binary(identifier("weight"), BinaryOperator::LessThan, Expression::UnaryOperation {
+                operand: Box::new(decimal("1.5e3")),
+                operator: UnaryOperator::Minus,
+                span: Span::default(),
+            }),
+        ));
+    }
+
+    #[test]
+    fn test_large_integer_literals() {
+        let statement = Parser::new("SELECT 18446744073709551616, -9223372036854775808 FROM t;", &GenericDialect).parse_statement().unwrap();
+
+        let Statement::Select { columns, .. } = statement else {
+            panic!("expected a SELECT statement");
+        };
+        assert_eq!(columns, vec![
+            Expression::Decimal("18446744073709551616".into(), Span::default()),
+            Expression::UnaryOperation {
+                operand: Box::new(number(9223372036854775808)),
+                operator: UnaryOperator::Minus,
+                span: Span::default(),
+            },
+        ]);
+    }
+
+    #[test]
+    fn test_varchar_size_out_of_range() {
+        let input = "CREATE TABLE t (name VARCHAR(99999999999999999999));";
+        let error = Parser::new(input, &GenericDialect).parse_statement().unwrap_err();
+
+        assert_eq!(error.kind(), &ParseErrorKind::NumberOutOfRange("99999999999999999999".to_string()));
+        assert_eq!(&input[error.span().range()], "99999999999999999999");
+        assert!(error.is_syntax());
+    }
+
+    #[test]
+    fn test_binary_literals() {
+        let input = "CREATE TABLE files (hash BLOB NOT NULL CHECK (hash != X'00'), flags BYTEA CHECK (flags != B'0'));";
+        let Statement::CreateTable { column_list, .. } = Parser::new(input, &GenericDialect).parse_statement().unwrap() else {
+            panic!("expected a CREATE TABLE statement");
+        };
+
+        assert_eq!(column_list[0].column_type, DBType::Blob);
+        assert_eq!(column_list[1].column_type, DBType::Blob);
+        assert_eq!(column_list[0].constraints[1], Constraint::Check(
+            binary(identifier("hash"), BinaryOperator::NotEqual, Expression::HexString("00".into(), Span::default())),
+            Span::default(),
+        ));
+        assert_eq!(column_list[1].constraints[0], Constraint::Check(
+            binary(identifier("flags"), BinaryOperator::NotEqual, Expression::BitString("0".into(), Span::default())),
+            Span::default(),
+        ));
+
+        let statement = Parser::new("SELECT id FROM files WHERE hash = 0xDEADBEEF;", &GenericDialect).parse_statement().unwrap();
+        let Statement::Select { r#where: Some(condition), .. } = statement else {
+            panic!("expected a SELECT statement with a WHERE clause");
+        };
+        assert_eq!(condition, binary(identifier("hash"), BinaryOperator::Equal, Expression::HexString("DEADBEEF".into(), Span::default())));
+    }
+
+    #[test]
+    fn test_dialect_operators() {
+        let input = "SELECT a || b, a % 2 * 3, -price::VARCHAR(10) FROM t WHERE a <> b;";
+        let Statement::Select { columns, r#where, .. } = Parser::new(input, &PostgresDialect).parse_statement().unwrap() else {
+            panic!("expected a SELECT statement");
+        };
+
+        assert_eq!(columns, vec![
+            binary(identifier("a"), BinaryOperator::Concat, identifier("b")),
+            binary(binary(identifier("a"), BinaryOperator::Modulo, number(2)), BinaryOperator::Multiply, number(3)),
+            Expression::UnaryOperation {
+                operand: Box::new(Expression::Cast {
+                    expression: Box::new(identifier("price")),
+                    data_type: DBType::Varchar(10),
+                    span: Span::default(),
+                }),
+                operator: UnaryOperator::Minus,
+                span: Span::default(),
+            },
+        ]);
+        assert_eq!(r#where, Some(binary(identifier("a"), BinaryOperator::NotEqual, identifier("b"))));
+
+        let input = "SELECT a FROM t WHERE a = 1 || b = 2;";
+        let Statement::Select { r#where, .. } = Parser::new(input, &MySqlDialect).parse_statement().unwrap() else {
+            panic!("expected a SELECT statement");
+        };
+        assert_eq!(r#where, Some(binary(
+            binary(identifier("a"), BinaryOperator::Equal, number(1)),
+            BinaryOperator::Or,
+            binary(identifier("b"), BinaryOperator::Equal, number(2)),
+        )));
+    }
+
+    #[test]
+    fn test_dialect_keywords() {
+        let input = "CREATE TABLE blobs (blob BYTEA);";
+        let Statement::CreateTable { column_list, .. } = Parser::new(input, &PostgresDialect).parse_statement().unwrap() else {
+            panic!("expected a CREATE TABLE statement");
+        };
+        assert_eq!(column_list[0].column_name, Ident::new("blob"));
+        assert_eq!(column_list[0].column_type, DBType::Blob);
+
+        let error = Parser::new(input, &SqliteDialect).parse_statement().unwrap_err();
+        assert!(matches!(error.kind(), ParseErrorKind::UnexpectedToken { .. }));
+    }
+
+    /// A dialect for a read-only connection, which only accepts queries.
+    struct ReadOnlyDialect;
+
+    impl Dialect for ReadOnlyDialect {
+        fn supports_statement(&self, keyword: &Keyword) -> bool {
+            keyword == &Keyword::Select
+        }
+    }
+
+    #[test]
+    fn test_dialect_statements() {
+        let input = "CREATE TABLE t (a INT);\nSELECT a FROM t;";
+        let (statements, errors) = Parser::new(input, &ReadOnlyDialect).parse_statements_with_recovery();
+
+        assert!(matches!(statements[..], [Statement::Select { .. }]));
+        assert_eq!(errors.len(), 1);
+        assert_eq!(
+            errors[0].kind(),
+            &ParseErrorKind::UnexpectedToken { found: Token::Keyword(Keyword::Create), expected: "a statement supported by this dialect".to_string() }
+        );
+        assert_eq!(errors[0].statement_index(), Some(0));
+        assert_eq!(&input[errors[0].span().range()], "CREATE");
+
+        assert_eq!(Parser::new(input, &GenericDialect).parse_statements().unwrap().len(), 2);
+    }
+
+    #[test]
+    fn test_non_reserved_keywords_as_identifiers() {
+        let input = "CREATE TABLE Key (key INT PRIMARY KEY, Check VARCHAR(10) CHECK (check != ''), desc BOOL);";
+        let Statement::CreateTable { table_name, column_list, .. } = Parser::new(input, &GenericDialect).parse_statement().unwrap() else {
+            panic!("expected a CREATE TABLE statement");
+        };
+        assert_eq!(table_name, Ident::new("Key"));
+        let names: Vec<&str> = column_list.iter().map(|column| column.column_name.value.as_ref()).collect();
+        assert_eq!(names, vec!["key", "Check", "desc"]);
+        assert_eq!(column_list[1].constraints[0], Constraint::Check(
+            binary(identifier("check"), BinaryOperator::NotEqual, Expression::String("".into(), Span::default())),
+            Span::default(),
+        ));
+
+        let statement = Parser::new("SELECT key, int FROM key ORDER BY desc DESC;", &GenericDialect).parse_statement().unwrap();
+        assert_eq!(statement, Statement::Select {
+            columns: vec![identifier("key"), identifier("int")],
+            from: Ident::new("key"),
+            r#where: None,
+            orderby: vec![Expression::UnaryOperation {
+                operand: Box::new(identifier("desc")),
+                operator: UnaryOperator::Desc,
+                span: Span::default(),
+            }],
+            span: Span::default(),
+        });
+
+        let error = Parser::new("CREATE TABLE t (from INT);", &GenericDialect).parse_statement().unwrap_err();
+        assert_eq!(error.kind(), &ParseErrorKind::UnexpectedToken {
+            expected: "a column name".to_string(),
+            found: Token::Keyword(Keyword::From),
+        });
+    }
+
+    #[test]
+    fn test_identifier_case_folding() {
+        let input = r#"SELECT Name, "Name" FROM Users;"#;
+        let normalized = |statement: Statement| {
+            let Statement::Select { columns, from, .. } = statement else {
+                panic!("expected a SELECT statement");
+            };
+            let mut names: Vec<String> = columns.into_iter().map(|column| match column {
+                Expression::Identifier(ident, _) => ident.normalized.into_owned(),
+                other => panic!("expected an identifier, found {:?}", other),
+            }).collect();
+            names.push(from.normalized.into_owned());
+            names
+        };
+
+        let statement = Parser::new(input, &GenericDialect).parse_statement().unwrap();
+        assert_eq!(normalized(statement), vec!["Name", "Name", "Users"]);
+
+        let statement = Parser::new(input, &PostgresDialect).parse_statement().unwrap();
+        assert_eq!(normalized(statement), vec!["name", "Name", "users"]);
+
+        let options = TokenizerOptions { case_folding: Some(CaseFolding::Upper), ..Default::default() };
+        let statement = Parser::with_options(input, &PostgresDialect, options).parse_statement().unwrap();
+        assert_eq!(normalized(statement), vec!["NAME", "Name", "USERS"]);
+
+        let statement = Parser::new("CREATE TABLE t (Key INT);", &PostgresDialect).parse_statement().unwrap();
+        let Statement::CreateTable { column_list, .. } = statement else {
+            panic!("expected a CREATE TABLE statement");
+        };
+        assert_eq!(column_list[0].column_name, Ident::folded("Key", CaseFolding::Lower));
+        assert_eq!(column_list[0].column_name.value, "Key");
+    }
+
+    #[test]
+    fn test_into_owned() {
+        let statement = {
+            let input = String::from("SELECT name FROM users WHERE name = 'O''Brien';");
+            let statement = Parser::new(&input, &GenericDialect).parse_statement().unwrap();
+            let Statement::Select { from, .. } = &statement else {
+                panic!("expected a SELECT statement");
+            };
+            assert!(matches!(from.value, Cow::Borrowed(_)));
+            statement.into_owned()
+        };
+
+        assert_eq!(statement, Statement::Select {
+            columns: vec![identifier("name")],
+            from: Ident::new("users"),
+            r#where: Some(binary(identifier("name"), BinaryOperator::Equal, Expression::String("O'Brien".into(), Span::default()))),
+            orderby: vec![],
+            span: Span::default(),
         });
     }
 }
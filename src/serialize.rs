@@ -0,0 +1,224 @@
+use std::borrow::Cow;
+
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+
+use crate::ident::Ident;
+use crate::span::Span;
+use crate::statement::{BinaryOperator, Constraint, DBType, Expression, UnaryOperator};
+
+// `Expression` and `Constraint` have tuple variants, which serde cannot tag internally. They are written through the mirrors below, which name every field, so every AST node is a JSON object with a `type` field, the named fields of its variant and a `span`.
+
+/// The JSON shape of an `Expression`, borrowing from the expression being written.
+#[derive(Serialize)]
+#[serde(tag = "type")]
+enum ExpressionRef<'r, 'a> {
+    BinaryOperation {
+        left_operand: &'r Expression<'a>,
+        operator: &'r BinaryOperator,
+        right_operand: &'r Expression<'a>,
+        span: &'r Span,
+    },
+    UnaryOperation {
+        operand: &'r Expression<'a>,
+        operator: &'r UnaryOperator,
+        span: &'r Span,
+    },
+    Cast {
+        expression: &'r Expression<'a>,
+        data_type: &'r DBType,
+        span: &'r Span,
+    },
+    Number { value: u64, span: &'r Span },
+    Decimal { value: &'r str, span: &'r Span },
+    HexString { value: &'r str, span: &'r Span },
+    BitString { value: &'r str, span: &'r Span },
+    Bool { value: bool, span: &'r Span },
+    Identifier { value: &'r Ident<'a>, span: &'r Span },
+    String { value: &'r str, span: &'r Span },
+}
+
+/// The JSON shape of an `Expression`, as it is read back.
+#[derive(Deserialize)]
+#[serde(tag = "type")]
+enum ExpressionOwned<'a> {
+    BinaryOperation {
+        left_operand: Box<Expression<'a>>,
+        operator: BinaryOperator,
+        right_operand: Box<Expression<'a>>,
+        span: Span,
+    },
+    UnaryOperation {
+        operand: Box<Expression<'a>>,
+        operator: UnaryOperator,
+        span: Span,
+    },
+    Cast {
+        expression: Box<Expression<'a>>,
+        data_type: DBType,
+        span: Span,
+    },
+    Number { value: u64, span: Span },
+    Decimal { value: Cow<'a, str>, span: Span },
+    HexString { value: Cow<'a, str>, span: Span },
+    BitString { value: Cow<'a, str>, span: Span },
+    Bool { value: bool, span: Span },
+    Identifier { value: Ident<'a>, span: Span },
+    String { value: Cow<'a, str>, span: Span },
+}
+
+impl Serialize for Expression<'_> {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        let shape = match self {
+            Expression::BinaryOperation { left_operand, operator, right_operand, span } => ExpressionRef::BinaryOperation {
+                left_operand,
+                operator,
+                right_operand,
+                span,
+            },
+            Expression::UnaryOperation { operand, operator, span } => ExpressionRef::UnaryOperation { operand, operator, span },
+            Expression::Cast { expression, data_type, span } => ExpressionRef::Cast { expression, data_type, span },
+            Expression::Number(value, span) => ExpressionRef::Number { value: *value, span },
+            Expression::Decimal(value, span) => ExpressionRef::Decimal { value, span },
+            Expression::HexString(value, span) => ExpressionRef::HexString { value, span },
+            Expression::BitString(value, span) => ExpressionRef::BitString { value, span },
+            Expression::Bool(value, span) => ExpressionRef::Bool { value: *value, span },
+            Expression::Identifier(value, span) => ExpressionRef::Identifier { value, span },
+            Expression::String(value, span) => ExpressionRef::String { value, span },
+        };
+        shape.serialize(serializer)
+    }
+}
+
+impl<'de, 'a> Deserialize<'de> for Expression<'a> {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let expression = match ExpressionOwned::deserialize(deserializer)? {
+            ExpressionOwned::BinaryOperation { left_operand, operator, right_operand, span } => Expression::BinaryOperation {
+                left_operand,
+                operator,
+                right_operand,
+                span,
+            },
+            ExpressionOwned::UnaryOperation { operand, operator, span } => Expression::UnaryOperation { operand, operator, span },
+            ExpressionOwned::Cast { expression, data_type, span } => Expression::Cast { expression, data_type, span },
+            ExpressionOwned::Number { value, span } => Expression::Number(value, span),
+            ExpressionOwned::Decimal { value, span } => Expression::Decimal(value, span),
+            ExpressionOwned::HexString { value, span } => Expression::HexString(value, span),
+            ExpressionOwned::BitString { value, span } => Expression::BitString(value, span),
+            ExpressionOwned::Bool { value, span } => Expression::Bool(value, span),
+            ExpressionOwned::Identifier { value, span } => Expression::Identifier(value, span),
+            ExpressionOwned::String { value, span } => Expression::String(value, span),
+        };
+        Ok(expression)
+    }
+}
+
+/// The JSON shape of a `Constraint`, borrowing from the constraint being written.
+#[derive(Serialize)]
+#[serde(tag = "type")]
+enum ConstraintRef<'r, 'a> {
+    NotNull { span: &'r Span },
+    PrimaryKey { span: &'r Span },
+    Check { expression: &'r Expression<'a>, span: &'r Span },
+}
+
+/// The JSON shape of a `Constraint`, as it is read back.
+#[derive(Deserialize)]
+#[serde(tag = "type")]
+enum ConstraintOwned<'a> {
+    NotNull { span: Span },
+    PrimaryKey { span: Span },
+    Check { expression: Expression<'a>, span: Span },
+}
+
+impl Serialize for Constraint<'_> {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        let shape = match self {
+            Constraint::NotNull(span) => ConstraintRef::NotNull { span },
+            Constraint::PrimaryKey(span) => ConstraintRef::PrimaryKey { span },
+            Constraint::Check(expression, span) => ConstraintRef::Check { expression, span },
+        };
+        shape.serialize(serializer)
+    }
+}
+
+impl<'de, 'a> Deserialize<'de> for Constraint<'a> {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let constraint = match ConstraintOwned::deserialize(deserializer)? {
+            ConstraintOwned::NotNull { span } => Constraint::NotNull(span),
+            ConstraintOwned::PrimaryKey { span } => Constraint::PrimaryKey(span),
+            ConstraintOwned::Check { expression, span } => Constraint::Check(expression, span),
+        };
+        Ok(constraint)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::json;
+
+    use crate::statement::Statement;
+    use crate::token::Token;
+    use crate::tokenizer::Tokenizer;
+    use crate::{parse_sql, GenericDialect};
+
+    fn round_trip(sql: &str) {
+        for statement in parse_sql(sql).unwrap() {
+            let json = serde_json::to_string(&statement).unwrap();
+            let read: Statement = serde_json::from_str(&json).unwrap();
+            assert_eq!(read, statement, "{}", json);
+            assert_eq!(read.span().range(), statement.span().range());
+        }
+    }
+
+    #[test]
+    fn test_round_trip() {
+        round_trip("SELECT a, -b::INT, 'it''s', 9.99, X'FF', B'10', TRUE, \"Quoted\" FROM t WHERE a >= 1 AND NOT b || 'x' = 'y' ORDER BY a DESC;");
+        round_trip("CREATE TABLE t (id INT PRIMARY KEY, name VARCHAR(10) NOT NULL CHECK (name != ''), data BLOB, flag BOOL);");
+    }
+
+    #[test]
+    fn test_statement_shape() {
+        let statement = &parse_sql("SELECT a FROM t WHERE a > 1;").unwrap()[0];
+        let span = |start: usize, end: usize| json!({ "start": start, "end": end, "line": 1, "column": start + 1 });
+        let ident = |name: &str| json!({ "value": name, "quote_style": null, "normalized": name });
+        assert_eq!(
+            serde_json::to_value(statement).unwrap(),
+            json!({
+                "type": "Select",
+                "columns": [{ "type": "Identifier", "value": ident("a"), "span": span(7, 8) }],
+                "from": ident("t"),
+                "where": {
+                    "type": "BinaryOperation",
+                    "left_operand": { "type": "Identifier", "value": ident("a"), "span": span(22, 23) },
+                    "operator": "GreaterThan",
+                    "right_operand": { "type": "Number", "value": 1, "span": span(26, 27) },
+                    "span": span(22, 27),
+                },
+                "orderby": [],
+                "span": span(0, 28),
+            })
+        );
+    }
+
+    #[test]
+    fn test_column_shape() {
+        let Statement::CreateTable { column_list, .. } = &parse_sql("CREATE TABLE t (a VARCHAR(5) NOT NULL, b INT);").unwrap()[0] else {
+            unreachable!()
+        };
+        let column = serde_json::to_value(&column_list[0]).unwrap();
+        assert_eq!(column["column_type"], json!({ "type": "Varchar", "value": 5 }));
+        assert_eq!(column["constraints"][0]["type"], "NotNull");
+        assert_eq!(serde_json::to_value(&column_list[1]).unwrap()["column_type"], json!({ "type": "Int" }));
+    }
+
+    #[test]
+    fn test_tokens() {
+        let tokens: Vec<Token> = Tokenizer::new("SELECT \"a\", 'b' ;", &GenericDialect).map(|result| result.unwrap().0).collect();
+        let json = serde_json::to_value(&tokens).unwrap();
+        assert_eq!(json[0], json!({ "type": "Keyword", "value": "Select" }));
+        assert_eq!(json[1], json!({ "type": "Identifier", "value": { "value": "a", "quote_style": "\"", "normalized": "a" } }));
+        assert_eq!(json[2], json!({ "type": "Comma" }));
+        assert_eq!(json[3], json!({ "type": "String", "value": "b" }));
+        assert_eq!(serde_json::from_value::<Vec<Token>>(json).unwrap(), tokens);
+    }
+}
@@ -0,0 +1,50 @@
+use std::fmt::{Display, Formatter};
+use std::ops::Range;
+
+/// The location of a piece of source text. `start` and `end` are byte offsets into the input (so `&input[span.range()]` is the original text), while `line` and `column` are the 1-based position of the first character, counted in characters, which is what gets shown to the user.
+///
+/// Spans describe where a value came from, not what it is, so any two spans compare equal. This keeps `==` on tokens, errors and AST nodes structural: the same query formatted differently still compares equal. Use `range()`, `line` and `column` to compare actual positions.
+#[derive(Debug, Clone, Copy, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Span {
+    pub start: usize,
+    pub end: usize,
+    pub line: usize,
+    pub column: usize,
+}
+
+impl Span {
+    pub fn new(start: usize, end: usize, line: usize, column: usize) -> Self {
+        Self {
+            start,
+            end,
+            line,
+            column,
+        }
+    }
+
+    pub fn range(&self) -> Range<usize> {
+        self.start..self.end
+    }
+
+    /// Returns the smallest span that covers both `self` and `other`.
+    pub fn union(&self, other: &Span) -> Span {
+        if other.start < self.start {
+            Span::new(other.start, self.end.max(other.end), other.line, other.column)
+        } else {
+            Span::new(self.start, self.end.max(other.end), self.line, self.column)
+        }
+    }
+}
+
+impl PartialEq for Span {
+    fn eq(&self, _other: &Self) -> bool {
+        true
+    }
+}
+
+impl Display for Span {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}:{}", self.line, self.column)
+    }
+}
@@ -1,17 +1,27 @@
+use std::borrow::Cow;
 use std::fmt::{Debug, Display, Formatter};
 
+use crate::dialect::{Dialect, GenericDialect};
+use crate::ident::Ident;
+use crate::span::Span;
+use crate::token::Keyword;
+
 /// The main entity of the whole parser. `Statement` is implemented as an enumeration because adding functionality is as easy as adding an enumeration constant and implementing functionality for that enumeration constant (implementation in the database command interpreter, which is not a part of this project). Parsing any correct `SELECT` or `CREATE`  (or `UPDATE`, `INSERT INTO`, ... hypothetically) statement should be turned into an instance of this enumeration. Ultimately, your main parser function (something like `build_statement(query: &str) -> Statement`) should return this enumeration.
 ///
 /// The `SELECT` statement has four components:
 /// 1. `columns` – A vector of columns from the selected table that the database should return.
-/// 2. `from` – A simple string, containing a table that is being queried (we aren't doing joins because they complicate stuff too much for this project).
+/// 2. `from` – An identifier (`Ident`), containing a table that is being queried (we aren't doing joins because they complicate stuff too much for this project).
 /// 3. `where` – A single expression that is the actual filter for the database query. It is wrapped in an `Option` because not every `SELECT` query contains a filter. The actual name is `r#where` because in Rust, `where` is a reserved keyword, and the prefix `r#` means: interpret this token as a raw string, do not check for keyword matches.
 /// 4. `orderby` – A vector of expressions that define how should the data be ordered. A vector is needed because the data can be ordered by the first column, and then all data that has the same first column can be ordered by the second column, ... Also, the data can be ordered not simply by columns, but by complex expressions as well.
 ///
 /// The `CREATE TABLE` statement has two components:
-/// 1. `table_name` – A simple string, the name of the table.
+/// 1. `table_name` – An identifier (`Ident`), the name of the table.
 /// 2. `column_list` – A vector of table column types, where each table column contains the definition of one column.
 ///
+/// Both statements also record their `span` – the part of the input from the first keyword up to and including the `;`. Every node of the tree (`Expression`, `TableColumn`, `Constraint`) records its span as well. Spans always compare equal, so they do not take part in `==` and are written as `_` in the examples below.
+///
+/// The tree borrows its identifiers and literals from the parsed input, which is what the `'a` lifetime stands for; `into_owned` copies them, for example to keep statements after the input buffer is gone.
+///
 /// Examples:
 ///
 /// ---
@@ -19,15 +29,16 @@ use std::fmt::{Debug, Display, Formatter};
 /// SELECT name, surname FROM users;
 /// ```
 /// is a `SELECT` statement that,  when parsed, looks like this:
-/// ```rust
+/// ```text
 /// Statement::Select {
 ///     columns: vec![
-/// 		Expression::Identifier("name".to_string()),
-/// 		Expression:Identifier("surname".to_string())
-/// 	],
-///     from: "users".to_string(),
+///         Expression::Identifier(Ident::new("name"), _),
+///         Expression::Identifier(Ident::new("surname"), _)
+///     ],
+///     from: Ident::new("users"),
 ///     r#where: None,
-///     orderby: vec![]
+///     orderby: vec![],
+///     span: _,
 /// }
 /// ```
 /// ---
@@ -35,49 +46,55 @@ use std::fmt::{Debug, Display, Formatter};
 /// SELECT age * 5, 'this is a string' FROM users;
 /// ```
 /// is a `SELECT` statement that,  when parsed, looks like this:
-/// ```rust
+/// ```text
 /// Statement::Select {
 ///     columns: vec![
 ///         Expression::BinaryOperation {
-///             left_operand: Box::new(Expression::Identifier("age".to_string())),
+///             left_operand: Box::new(Expression::Identifier(Ident::new("age"), _)),
 ///             operator: BinaryOperator::Multiply,
-///             right_operand: Box::new(Expression::Number(5)),
+///             right_operand: Box::new(Expression::Number(5, _)),
+///             span: _,
 ///         },
-///         Expression::String("this is a string".to_string()),
+///         Expression::String("this is a string".into(), _),
 ///     ],
-///     from: "users".to_string(),
+///     from: Ident::new("users"),
 ///     r#where: None,
-///     orderby: vec![]
+///     orderby: vec![],
+///     span: _,
 /// }
 /// ```
 /// ---
 /// ```sql
-/// SELECT name, surname FROM users WHERE name = \"Voldemort\" AND surname = 'Riddle';
+/// SELECT name, surname FROM users WHERE name = 'Voldemort' AND surname = 'Riddle';
 /// ```
 /// is a  `SELECT` statement that, when parsed, looks like this:
-/// ```rust
+/// ```text
 /// Statement::Select {
-///     columns: [
-///         Expression::Identifier("name".to_string()),
-///         Expression::Identifier("surname".to_string()),
+///     columns: vec![
+///         Expression::Identifier(Ident::new("name"), _),
+///         Expression::Identifier(Ident::new("surname"), _),
 ///     ],
-///     from: "users".to_string(),
+///     from: Ident::new("users"),
 ///     r#where: Some(
 ///         Expression::BinaryOperation {
 ///             left_operand: Box::new(Expression::BinaryOperation {
-///                 left_operand: Box::new(Expression::Identifier("name".to_string())),
-///                 operand: BinaryOperator::Equals,
-///                 right_operand: Box::new(Expression::String("Voldemort".to_string())),
+///                 left_operand: Box::new(Expression::Identifier(Ident::new("name"), _)),
+///                 operator: BinaryOperator::Equal,
+///                 right_operand: Box::new(Expression::String("Voldemort".into(), _)),
+///                 span: _,
 ///             }),
-///             operand: BinaryOperator::And,
+///             operator: BinaryOperator::And,
 ///             right_operand: Box::new(Expression::BinaryOperation {
-///                 left_operand: Box::new(Expression::Identifier("surname".to_string())),
-///                 operand: BinaryOperator::Equals,
-///                 right_operand: Box::new(Expression::String("Riddle".to_string())),
+///                 left_operand: Box::new(Expression::Identifier(Ident::new("surname"), _)),
+///                 operator: BinaryOperator::Equal,
+///                 right_operand: Box::new(Expression::String("Riddle".into(), _)),
+///                 span: _,
 ///             }),
+///             span: _,
 ///         },
 ///     ),
-///     orderby: vec![]
+///     orderby: vec![],
+///     span: _,
 /// }
 /// ```
 ///  ---
@@ -85,32 +102,37 @@ use std::fmt::{Debug, Display, Formatter};
 /// SELECT id, salary FROM users ORDER BY salary - 2 * 10 ASC, id DESC;
 /// ```
 /// is a  `SELECT` statement that, when parsed, looks like this:
-/// ```rust
+/// ```text
 /// Statement::Select {
 ///     columns: vec![
-///         Expression::Identifier("id".to_string()),
-///         Expression::Identifier("salary".to_string()),
+///         Expression::Identifier(Ident::new("id"), _),
+///         Expression::Identifier(Ident::new("salary"), _),
 ///     ],
-///     from: "users".to_string(),
+///     from: Ident::new("users"),
 ///     r#where: None,
 ///     orderby: vec![
 ///         Expression::UnaryOperation {
 ///             operand: Box::new(Expression::BinaryOperation {
-///                 left_operand: Box::new(Expression::Identifier("salary".to_string())),
+///                 left_operand: Box::new(Expression::Identifier(Ident::new("salary"), _)),
 ///                 operator: BinaryOperator::Minus,
 ///                 right_operand: Box::new(Expression::BinaryOperation {
-///                     left_operand: Box::new(Expression::Number(2)),
+///                     left_operand: Box::new(Expression::Number(2, _)),
 ///                     operator: BinaryOperator::Multiply,
-///                     right_operand: Box::new(Expression::Number(10)),
+///                     right_operand: Box::new(Expression::Number(10, _)),
+///                     span: _,
 ///                 }),
+///                 span: _,
 ///             }),
 ///             operator: UnaryOperator::Asc,
+///             span: _,
 ///         },
 ///         Expression::UnaryOperation {
-///             operand: Box::new(Expression::Identifier("id".to_string())),
+///             operand: Box::new(Expression::Identifier(Ident::new("id"), _)),
 ///             operator: UnaryOperator::Desc,
+///             span: _,
 ///         },
 ///     ],
+///     span: _,
 /// }
 /// ```
 ///  ---
@@ -118,108 +140,122 @@ use std::fmt::{Debug, Display, Formatter};
 /// SELECT id FROM registered_users WHERE password_encryption = TRUE ORDER BY id DESC;
 /// ```
 /// is a  `SELECT` statement that, when parsed, looks like this:
-/// ```rust
+/// ```text
 /// Statement::Select {
 ///     columns: vec![
-///         Expression::Identifier("id".to_string())
+///         Expression::Identifier(Ident::new("id"), _)
 ///     ],
-///     from: "registered_users".to_string(),
+///     from: Ident::new("registered_users"),
 ///     r#where: Some(
 ///         Expression::BinaryOperation {
-///             left_operand: Box::new(Expression::Identifier("password_encryption".to_string())),
-///             operator: BinaryOperator::Equals,
-///             right_operand: Box::new(Expression::Bool(true))
+///             left_operand: Box::new(Expression::Identifier(Ident::new("password_encryption"), _)),
+///             operator: BinaryOperator::Equal,
+///             right_operand: Box::new(Expression::Bool(true, _)),
+///             span: _,
 ///         }
 ///     ),
 ///     orderby: vec![
 ///         Expression::UnaryOperation {
-///             operand: Box::new(Expression::Identifier("id".to_string())),
-///             operator: UnaryOperator::Desc
+///             operand: Box::new(Expression::Identifier(Ident::new("id"), _)),
+///             operator: UnaryOperator::Desc,
+///             span: _,
 ///         }
-///     ]
+///     ],
+///     span: _,
 /// }
 /// ```
 /// ---
 /// ```sql
 /// CREATE TABLE simple_table(
-/// 	int_col INT,
-/// 	string_col VARCHAR(255),
-/// 	bool_col BOOL
+///     int_col INT,
+///     string_col VARCHAR(255),
+///     bool_col BOOL
 /// );
 /// ```
 /// is a  `CREATE TABLE` statement that, when parsed, looks like this:
-/// ```rust
+/// ```text
 /// Statement::CreateTable {
-///     table_name: "simple_table".to_string(),
+///     table_name: Ident::new("simple_table"),
 ///     column_list: vec![
 ///         TableColumn {
-///             column_name: "int_col".to_string(),
+///             column_name: Ident::new("int_col"),
 ///             column_type: DBType::Int,
 ///             constraints: vec![],
+///             span: _,
 ///         },
 ///         TableColumn {
-///             column_name: "string_col".to_string(),
+///             column_name: Ident::new("string_col"),
 ///             column_type: DBType::Varchar(255),
 ///             constraints: vec![],
+///             span: _,
 ///         },
 ///         TableColumn {
-///             column_name: "bool_col".to_string(),
+///             column_name: Ident::new("bool_col"),
 ///             column_type: DBType::Bool,
 ///             constraints: vec![],
+///             span: _,
 ///         },
-///     ]
+///     ],
+///     span: _,
 /// }
 /// ```
 /// ---
 /// ```sql
 /// CREATE TABLE complex_table(
-/// 	id INT PRIMARY KEY,
-/// 	email VARCHAR(255) NOT NULL,
-/// 	is_junior BOOL,
-/// 	age INT CHECK(age >= 18) CHECK(age <= 65)
+///     id INT PRIMARY KEY,
+///     email VARCHAR(255) NOT NULL,
+///     is_junior BOOL,
+///     age INT CHECK(age >= 18) CHECK(age <= 65)
 /// );
 /// ```
 /// is a  `CREATE TABLE` statement that, when parsed, looks like this:
-/// ```rust
+/// ```text
 /// Statement::CreateTable {
-///     table_name: Expression::Identifier("complex_table".to_string()),
+///     table_name: Ident::new("complex_table"),
 ///     column_list: vec![
 ///         TableColumn {
-///             column_name: Expression::Identifier("id".to_string()),
+///             column_name: Ident::new("id"),
 ///             column_type: DBType::Int,
 ///             constraints: vec![
-///                 Constraint::PrimaryKey,
+///                 Constraint::PrimaryKey(_),
 ///             ],
+///             span: _,
 ///         },
 ///         TableColumn {
-///             column_name: Expression::Identifier("email".to_string()),
+///             column_name: Ident::new("email"),
 ///             column_type: DBType::Varchar(255),
 ///             constraints: vec![
-///                 Constraint::NotNull,
+///                 Constraint::NotNull(_),
 ///             ],
+///             span: _,
 ///         },
 ///         TableColumn {
-///             column_name: Expression::Identifier("is_junior".to_string()),
+///             column_name: Ident::new("is_junior"),
 ///             column_type: DBType::Bool,
 ///             constraints: vec![],
+///             span: _,
 ///         },
 ///         TableColumn {
-///             column_name: Expression::Identifier("age".to_string()),
+///             column_name: Ident::new("age"),
 ///             column_type: DBType::Int,
 ///             constraints: vec![
 ///                 Constraint::Check(Expression::BinaryOperation {
-///                     left_operand: Box::new(Expression::Identifier("age".to_string())),
+///                     left_operand: Box::new(Expression::Identifier(Ident::new("age"), _)),
 ///                     operator: BinaryOperator::GreaterThanOrEqual,
-///                     right_operand: Box::new(Expression::Number(18)),
+///                     right_operand: Box::new(Expression::Number(18, _)),
+///                     span: _,
 ///                 }),
 ///                 Constraint::Check(Expression::BinaryOperation {
-///                     left_operand: Box::new(Expression::Identifier("age".to_string())),
+///                     left_operand: Box::new(Expression::Identifier(Ident::new("age"), _)),
 ///                     operator: BinaryOperator::LessThanOrEqual,
-///                     right_operand: Box::new(Expression::Number(65)),
+///                     right_operand: Box::new(Expression::Number(65, _)),
+///                     span: _,
 ///                 }),
 ///             ],
+///             span: _,
 ///         },
 ///     ],
+///     span: _,
 /// }
 /// ```
 /// ---
@@ -234,184 +270,320 @@ use std::fmt::{Debug, Display, Formatter};
 /// ```
 /// is a string, that, the parser should throw an error to the user when it encounters it (no semicolon at the end).
 #[derive(Debug, PartialEq)]
-pub enum Statement {
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize), serde(tag = "type"))]
+pub enum Statement<'a> {
     Select {
-        columns: Vec<Expression>,
-        from: String,
-        r#where: Option<Expression>,
-        orderby: Vec<Expression>,
+        columns: Vec<Expression<'a>>,
+        from: Ident<'a>,
+        r#where: Option<Expression<'a>>,
+        orderby: Vec<Expression<'a>>,
+        span: Span,
     },
     CreateTable {
-        table_name: String,
-        column_list: Vec<TableColumn>,
+        table_name: Ident<'a>,
+        column_list: Vec<TableColumn<'a>>,
+        span: Span,
     }
 }
 
 /// The main entity of the expression parser. The Expression enum is structured like this, where an expression can contain another expression. This naturally allows us to represent complex expressions as trees. `Box<T>` smart pointers are used on unary and binary types of expressions because the compiler needs to know the size of the enum at compile time which is impossible when an enum contains itself (infinite size).
 ///
 /// An expression can be:
-/// * complex - a number of other expressions (tree-like structure, unary and binary operations)
-/// * a single number
-/// * a single identifier (like a variable 'x')
-/// * a single string (when doing parsing of WHERE statements that do operations with strings, strings must be in matching quotes – either `""` or `''`)
+/// * complex - a number of other expressions (tree-like structure, unary and binary operations, and `expression::type` casts where the dialect supports them)
+/// * a single number – `Number` for whole numbers, `Decimal` for literals with a fractional part or an exponent (`9.99`, `1e6`) and for whole numbers larger than `u64::MAX`, kept as the original text so that no precision is lost. A negative number is a unary minus applied to the literal, so `-9223372036854775808` (`i64::MIN`) is represented without overflow
+/// * a single identifier (like a variable 'x'), which may be quoted (`"order"`) to use a keyword or special characters as a name – the `Ident` records whether it was
+/// * a single string (when doing parsing of WHERE statements that do operations with strings, strings must be in single quotes `''`)
+/// * a byte string – `HexString` for `X'DEADBEEF'` and `0xFF`, `BitString` for `B'1010'`, both holding the digits as written
 /// * a boolean (only true or false)
 ///
 /// Examples:
 ///
 /// ---
-/// ```
+/// ```text
 /// (13 + 7) - 4
 /// ```
 /// is an expression that contains two expressions:
 /// 1. `(13 + 7)` which is
-/// ```rust
+/// ```text
 /// Expression::BinaryOperation {
-///     left_operand: Box::new(Expression::Number(13)),
+///     left_operand: Box::new(Expression::Number(13, _)),
 ///     operator: BinaryOperator::Plus,
-///     right_operand: Box::new(Expression::Number(7))
+///     right_operand: Box::new(Expression::Number(7, _)),
+///     span: _,
 /// }
 /// ```
 /// 2. `4` which is
-/// ```rust
-/// Expression::Number(4)
+/// ```text
+/// Expression::Number(4, _)
 /// ```
 /// Therefore, the whole expression after parsing should look like this:
-/// ```rust
+/// ```text
 /// Expression::BinaryOperation {
-///     left_operand: Expression::BinaryOperation {
-///         left_operand: Box::new(Expression::Number(13)),
+///     left_operand: Box::new(Expression::BinaryOperation {
+///         left_operand: Box::new(Expression::Number(13, _)),
 ///         operator: BinaryOperator::Plus,
-///         right_operand: Box::new(Expression::Number(7))
-///     },
+///         right_operand: Box::new(Expression::Number(7, _)),
+///         span: _,
+///     }),
 ///     operator: BinaryOperator::Minus,
-///     right_operand: Box::new(Expression::Number(4))
+///     right_operand: Box::new(Expression::Number(4, _)),
+///     span: _,
 /// }
 /// ```
 /// ---
-/// ```
-/// (5 - x) < (4 + y) OR name = "Donna"
+/// ```text
+/// (5 - x) < (4 + y) OR name = 'Donna'
 /// ```
 /// is an expression that contains five (three small and two combining) expressions:
 /// 1. `(5 - x)` which is
-/// ```rust
+/// ```text
 /// Expression::BinaryOperation {
-///     left_operand: Box::new(Expression::Number(5)),
+///     left_operand: Box::new(Expression::Number(5, _)),
 ///     operator: BinaryOperator::Minus,
-///     right_operand: Box::new(Expression::Identifier("x".to_string())),
+///     right_operand: Box::new(Expression::Identifier(Ident::new("x"), _)),
+///     span: _,
 /// }
 /// ```
-/// 2. `(4 - y)` which is
-/// ```rust
+/// 2. `(4 + y)` which is
+/// ```text
 /// Expression::BinaryOperation {
-///     left_operand: Box::new(Expression::Number(4)),
+///     left_operand: Box::new(Expression::Number(4, _)),
 ///     operator: BinaryOperator::Plus,
-///     right_operand: Box::new(Expression::Identifier("y".to_string()))
+///     right_operand: Box::new(Expression::Identifier(Ident::new("y"), _)),
+///     span: _,
 /// }
 /// ```
-/// 3. `name = "Donna"` which is
-/// ```rust
+/// 3. `name = 'Donna'` which is
+/// ```text
 /// Expression::BinaryOperation {
-///     left_operand: Box::new(Expression::Identifier("name".to_string())),
+///     left_operand: Box::new(Expression::Identifier(Ident::new("name"), _)),
 ///     operator: BinaryOperator::Equal,
-///     right_operand: Box::new(Expression::String("Donna".to_string()))
+///     right_operand: Box::new(Expression::String("Donna".into(), _)),
+///     span: _,
 /// }
 /// ```
 /// Therefore, the whole expression after parsing should look like this:
-/// ```rust
+/// ```text
 /// Expression::BinaryOperation {
 ///     left_operand: Box::new(Expression::BinaryOperation {
 ///         left_operand: Box::new(Expression::BinaryOperation {
-///             left_operand: Box::new(Expression::Number(5)),
+///             left_operand: Box::new(Expression::Number(5, _)),
 ///             operator: BinaryOperator::Minus,
-///             right_operand: Box::new(Expression::Identifier("x".to_string()))
+///             right_operand: Box::new(Expression::Identifier(Ident::new("x"), _)),
+///             span: _,
 ///         }),
 ///         operator: BinaryOperator::LessThan,
 ///         right_operand: Box::new(Expression::BinaryOperation {
-///             left_operand: Box::new(Expression::Number(4)),
+///             left_operand: Box::new(Expression::Number(4, _)),
 ///             operator: BinaryOperator::Plus,
-///             right_operand: Box::new(Expression::Identifier("y".to_string()))
-///         })
+///             right_operand: Box::new(Expression::Identifier(Ident::new("y"), _)),
+///             span: _,
+///         }),
+///         span: _,
 ///     }),
 ///     operator: BinaryOperator::Or,
 ///     right_operand: Box::new(Expression::BinaryOperation {
-///         left_operand: Box::new(Expression::Identifier("name".to_string())),
+///         left_operand: Box::new(Expression::Identifier(Ident::new("name"), _)),
 ///         operator: BinaryOperator::Equal,
-///         right_operand: Box::new(Expression::String("Donna".to_string()))
-///     })
+///         right_operand: Box::new(Expression::String("Donna".into(), _)),
+///         span: _,
+///     }),
+///     span: _,
 /// }
 /// ```
 /// ---
-/// ```
+/// ```text
 /// NOT some_boolean = TRUE
 /// ```
 /// should look like this:
-/// ```rust
+/// ```text
 /// Expression::BinaryOperation {
 ///     left_operand: Box::new(Expression::UnaryOperation {
-///         left_operand: Box::new(Expression::Identifier("some_boolean".to_string())),
-///         operator: UnaryOperator::Not
+///         operand: Box::new(Expression::Identifier(Ident::new("some_boolean"), _)),
+///         operator: UnaryOperator::Not,
+///         span: _,
 ///     }),
 ///     operator: BinaryOperator::Equal,
-///     right_operand: Box::new(Expression::Bool(true))
+///     right_operand: Box::new(Expression::Bool(true, _)),
+///     span: _,
 /// }
 /// ```
 /// ---
-/// ```
+/// ```text
 /// 5 * 3 - 4 + c / (13 -)
 /// ```
 /// is a string, that, the parser should throw an error to the user when it encounters it.
 
 #[derive(Debug, PartialEq)]
-pub enum Expression {
+pub enum Expression<'a> {
     BinaryOperation {
-        left_operand: Box<Expression>,
+        left_operand: Box<Expression<'a>>,
         operator: BinaryOperator,
-        right_operand: Box<Expression>,
+        right_operand: Box<Expression<'a>>,
+        span: Span,
     },
     UnaryOperation {
-        operand: Box<Expression>,
+        operand: Box<Expression<'a>>,
         operator: UnaryOperator,
+        span: Span,
+    },
+    Cast {
+        expression: Box<Expression<'a>>,
+        data_type: DBType,
+        span: Span,
     },
-    Number(u64),
-    Bool(bool),
-    Identifier(String),
-    String(String),
+    Number(u64, Span),
+    Decimal(Cow<'a, str>, Span),
+    HexString(Cow<'a, str>, Span),
+    BitString(Cow<'a, str>, Span),
+    Bool(bool, Span),
+    Identifier(Ident<'a>, Span),
+    String(Cow<'a, str>, Span),
 }
 
 /// A structure containing a definition for one column, when creating a table.
-/// 1. `column_name` – A simple string, representing a name.
+/// 1. `column_name` – An identifier (`Ident`), representing a name.
 /// 2. `column_type` – The type of the column. Types are defined in the `DBType` enum.
 /// 3.  `constraints` – A vector of constraints on the column. Types of constraints are defined in the `Constraint` enum.
+/// 4. `span` – The part of the input from the column name up to the end of the last constraint.
 #[derive(Debug, PartialEq)]
-pub struct TableColumn {
-    pub column_name: String,
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct TableColumn<'a> {
+    pub column_name: Ident<'a>,
     pub column_type: DBType,
-    pub constraints: Vec<Constraint>,
+    pub constraints: Vec<Constraint<'a>>,
+    pub span: Span,
 }
 
-/// A column in the database can be any of these types. `Int` and `Bool` types have no additional info, while the `Varchar(n)` type has an additional argument – the length of the string. Adding a type, such as `DECIMAL(n, m)` is boiled down to adding tokens for that type, parsing that type and adding it to this enum.
+/// A column in the database can be any of these types. `Int`, `Bool` and `Blob` (spelled `BLOB` or `BYTEA`) types have no additional info, while the `Varchar(n)` type has an additional argument – the length of the string. Adding a type, such as `DECIMAL(n, m)` is boiled down to adding tokens for that type, parsing that type and adding it to this enum.
 #[derive(Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize), serde(tag = "type", content = "value"))]
 pub enum DBType {
     Int,
     Varchar(usize),
     Bool,
+    Blob,
 }
 
-/// A column can be limited to a domain of values, which is defined by constraints on that column. `PrimaryKey` and `NotNull` constraints have no additional info, while the `Check` constraints has an additional argument – the expression which every table row must satisfy.
+/// A column can be limited to a domain of values, which is defined by constraints on that column. `PrimaryKey` and `NotNull` constraints have no additional info besides their span, while the `Check` constraints has an additional argument – the expression which every table row must satisfy.
 #[derive(Debug, PartialEq)]
-pub enum Constraint {
-    NotNull,
-    PrimaryKey,
-    Check(Expression)
+pub enum Constraint<'a> {
+    NotNull(Span),
+    PrimaryKey(Span),
+    Check(Expression<'a>, Span)
+}
+
+impl Statement<'_> {
+    pub fn span(&self) -> Span {
+        match self {
+            Statement::Select { span, .. } | Statement::CreateTable { span, .. } => *span,
+        }
+    }
+
+    /// Copies every identifier and literal borrowed from the input, so the statement can outlive the string it was parsed from.
+    pub fn into_owned(self) -> Statement<'static> {
+        match self {
+            Statement::Select { columns, from, r#where, orderby, span } => Statement::Select {
+                columns: columns.into_iter().map(Expression::into_owned).collect(),
+                from: from.into_owned(),
+                r#where: r#where.map(Expression::into_owned),
+                orderby: orderby.into_iter().map(Expression::into_owned).collect(),
+                span,
+            },
+            Statement::CreateTable { table_name, column_list, span } => Statement::CreateTable {
+                table_name: table_name.into_owned(),
+                column_list: column_list.into_iter().map(TableColumn::into_owned).collect(),
+                span,
+            },
+        }
+    }
+}
+
+impl Expression<'_> {
+    /// Copies every identifier and literal borrowed from the input, so the expression can outlive the string it was parsed from.
+    pub fn into_owned(self) -> Expression<'static> {
+        match self {
+            Expression::BinaryOperation { left_operand, operator, right_operand, span } => Expression::BinaryOperation {
+                left_operand: Box::new(left_operand.into_owned()),
+                operator,
+                right_operand: Box::new(right_operand.into_owned()),
+                span,
+            },
+            Expression::UnaryOperation { operand, operator, span } => Expression::UnaryOperation {
+                operand: Box::new(operand.into_owned()),
+                operator,
+                span,
+            },
+            Expression::Cast { expression, data_type, span } => Expression::Cast {
+                expression: Box::new(expression.into_owned()),
+                data_type,
+                span,
+            },
+            Expression::Number(number, span) => Expression::Number(number, span),
+            Expression::Decimal(number, span) => Expression::Decimal(Cow::Owned(number.into_owned()), span),
+            Expression::HexString(digits, span) => Expression::HexString(Cow::Owned(digits.into_owned()), span),
+            Expression::BitString(digits, span) => Expression::BitString(Cow::Owned(digits.into_owned()), span),
+            Expression::Bool(value, span) => Expression::Bool(value, span),
+            Expression::Identifier(ident, span) => Expression::Identifier(ident.into_owned(), span),
+            Expression::String(string, span) => Expression::String(Cow::Owned(string.into_owned()), span),
+        }
+    }
+
+    pub fn span(&self) -> Span {
+        match self {
+            Expression::BinaryOperation { span, .. }
+            | Expression::UnaryOperation { span, .. }
+            | Expression::Cast { span, .. } => *span,
+            Expression::Number(_, span)
+            | Expression::Decimal(_, span)
+            | Expression::HexString(_, span)
+            | Expression::BitString(_, span)
+            | Expression::Bool(_, span)
+            | Expression::Identifier(_, span)
+            | Expression::String(_, span) => *span,
+        }
+    }
+}
+
+impl TableColumn<'_> {
+    /// Copies the column name and any `CHECK` expressions out of the input.
+    pub fn into_owned(self) -> TableColumn<'static> {
+        TableColumn {
+            column_name: self.column_name.into_owned(),
+            column_type: self.column_type,
+            constraints: self.constraints.into_iter().map(Constraint::into_owned).collect(),
+            span: self.span,
+        }
+    }
+}
+
+impl Constraint<'_> {
+    pub fn span(&self) -> Span {
+        match self {
+            Constraint::NotNull(span) | Constraint::PrimaryKey(span) | Constraint::Check(_, span) => *span,
+        }
+    }
+
+    /// Copies a `CHECK` expression out of the input.
+    pub fn into_owned(self) -> Constraint<'static> {
+        match self {
+            Constraint::NotNull(span) => Constraint::NotNull(span),
+            Constraint::PrimaryKey(span) => Constraint::PrimaryKey(span),
+            Constraint::Check(expression, span) => Constraint::Check(expression.into_owned(), span),
+        }
+    }
 }
 
 /// Binary and unary operators are defined as enums, where each enumeration constant represents one operator. Binary and unary operators are defined separately because a `-` (minus), for example can be in a binary operation: `5 - 4`, as well as in a unary operation: `-2`. `Asc` and `Desc` are `ORDER BY` operators that have the lowest operator precedence in any expression. While both unary and binary operators may be the exact same as tokens that represent them, it is important to make a distinction between them, as they are used in different contexts.
-#[derive(Debug, PartialEq)]
+#[derive(Debug, PartialEq, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum BinaryOperator {
     Plus,
     Minus,
     Multiply,
     Divide,
+    Modulo,
+    Concat,
     GreaterThan,
     GreaterThanOrEqual,
     LessThan,
@@ -423,7 +595,8 @@ pub enum BinaryOperator {
 }
 
 /// Binary and unary operators are defined as enums, where each enumeration constant represents one operator. Binary and unary operators are defined separately because a `-` (minus), for example can be in a binary operation: `5 - 4`, as well as in a unary operation: `-2`. `Asc` and `Desc` are `ORDER BY` operators that have the lowest operator precedence in any expression. While both unary and binary operators may be the exact same as tokens that represent them, it is important to make a distinction between them, as they are used in different contexts.
-#[derive(Debug, PartialEq)]
+#[derive(Debug, PartialEq, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum UnaryOperator {
     Not,
     Plus,
@@ -432,6 +605,35 @@ pub enum UnaryOperator {
     Desc,
 }
 
+impl BinaryOperator {
+    /// The binding power of the operator, shared by the parser and by `Display`: a higher number binds tighter, and operators of equal precedence associate to the left, so `a - b - c` is `(a - b) - c`.
+    pub fn precedence(&self) -> u8 {
+        match self {
+            BinaryOperator::Or => 1,
+            BinaryOperator::And => 2,
+            BinaryOperator::Equal | BinaryOperator::NotEqual => 3,
+            BinaryOperator::GreaterThan | BinaryOperator::GreaterThanOrEqual |
+            BinaryOperator::LessThan | BinaryOperator::LessThanOrEqual => 4,
+            BinaryOperator::Concat => 5,
+            BinaryOperator::Plus | BinaryOperator::Minus => 6,
+            BinaryOperator::Multiply | BinaryOperator::Divide | BinaryOperator::Modulo => 7,
+        }
+    }
+}
+
+impl UnaryOperator {
+    /// The binding power of the operator. The prefix operators bind tighter than any binary operator (so `NOT a = b` is `(NOT a) = b`), while `ASC` and `DESC` apply to a whole `ORDER BY` expression.
+    pub fn precedence(&self) -> u8 {
+        match self {
+            UnaryOperator::Not | UnaryOperator::Plus | UnaryOperator::Minus => 8,
+            UnaryOperator::Asc | UnaryOperator::Desc => 0,
+        }
+    }
+}
+
+/// The binding power of a `::` cast, which is tighter than any prefix operator.
+const CAST_PRECEDENCE: u8 = 9;
+
 // Example manual implementations for Display traits.
 // Automatic derivation of those traits can be done, but the actual printing
 // will be the same as in Debug prints which is not useful
@@ -460,6 +662,8 @@ impl Display for BinaryOperator {
             BinaryOperator::NotEqual => write!(f, "!="),
             BinaryOperator::Multiply => write!(f, "*"),
             BinaryOperator::Divide => write!(f, "/"),
+            BinaryOperator::Modulo => write!(f, "%"),
+            BinaryOperator::Concat => write!(f, "||"),
             BinaryOperator::Minus => write!(f, "-"),
             BinaryOperator::Plus => write!(f, "+"),
             BinaryOperator::And => write!(f, "AND"),
@@ -468,19 +672,391 @@ impl Display for BinaryOperator {
     }
 }
 
-impl Display for Expression {
-    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+impl Expression<'_> {
+    /// The binding power of the expression's outermost operator; literals and identifiers never need parentheses.
+    pub(crate) fn precedence(&self) -> u8 {
         match self {
-            Expression::BinaryOperation { left_operand, operator, right_operand } => {
-                write!(f, "({:?} {:?} {:?})", left_operand, operator, right_operand)
+            Expression::BinaryOperation { operator, .. } => operator.precedence(),
+            Expression::UnaryOperation { operator, .. } => operator.precedence(),
+            Expression::Cast { .. } => CAST_PRECEDENCE,
+            _ => u8::MAX,
+        }
+    }
+}
+
+/// A node rendered as SQL for a specific dialect, returned by the `sql` methods of `Statement`, `Expression`, `TableColumn`, `Constraint`, `Ident` and `DBType`. Its `Display` output is read back into an equal node by a parser for the same dialect.
+pub struct Sql<'r, T: ?Sized> {
+    node: &'r T,
+    dialect: &'r dyn Dialect,
+}
+
+impl Statement<'_> {
+    /// Renders the statement for `dialect`; see `Display for Statement`, which renders for the generic dialect.
+    pub fn sql<'r>(&'r self, dialect: &'r dyn Dialect) -> Sql<'r, Self> {
+        Sql { node: self, dialect }
+    }
+}
+
+impl Expression<'_> {
+    /// Renders the expression for `dialect`.
+    pub fn sql<'r>(&'r self, dialect: &'r dyn Dialect) -> Sql<'r, Self> {
+        Sql { node: self, dialect }
+    }
+}
+
+impl TableColumn<'_> {
+    /// Renders the column definition for `dialect`.
+    pub fn sql<'r>(&'r self, dialect: &'r dyn Dialect) -> Sql<'r, Self> {
+        Sql { node: self, dialect }
+    }
+}
+
+impl Constraint<'_> {
+    /// Renders the constraint for `dialect`.
+    pub fn sql<'r>(&'r self, dialect: &'r dyn Dialect) -> Sql<'r, Self> {
+        Sql { node: self, dialect }
+    }
+}
+
+impl Ident<'_> {
+    /// Renders the identifier for `dialect`, changing the quote character if the dialect does not accept it.
+    pub fn sql<'r>(&'r self, dialect: &'r dyn Dialect) -> Sql<'r, Self> {
+        Sql { node: self, dialect }
+    }
+}
+
+impl DBType {
+    /// Renders the type for `dialect`: `BLOB`, or `BYTEA` where `BLOB` is not a keyword.
+    pub fn sql<'r>(&'r self, dialect: &'r dyn Dialect) -> Sql<'r, Self> {
+        Sql { node: self, dialect }
+    }
+}
+
+/// Writes `expression` for `dialect`, in parentheses if `parenthesize` is set.
+fn write_operand(f: &mut Formatter<'_>, expression: &Expression<'_>, dialect: &dyn Dialect, parenthesize: bool) -> std::fmt::Result {
+    if parenthesize {
+        write!(f, "({})", expression.sql(dialect))
+    } else {
+        write!(f, "{}", expression.sql(dialect))
+    }
+}
+
+/// Writes `items` separated by `, `, each with `write`.
+fn write_list<T>(f: &mut Formatter<'_>, items: &[T], mut write: impl FnMut(&mut Formatter<'_>, &T) -> std::fmt::Result) -> std::fmt::Result {
+    for (index, item) in items.iter().enumerate() {
+        if index > 0 {
+            write!(f, ", ")?;
+        }
+        write(f, item)?;
+    }
+    Ok(())
+}
+
+/// Writes `ident` so that `dialect` reads it back. An unquoted identifier is written as it is; a quoted one keeps its quote character if the dialect accepts it, and otherwise uses the first of `"`, `` ` `` and `[` that it does, so MySQL's `` `order` `` becomes `"order"` in the generic dialect.
+fn write_ident(f: &mut Formatter<'_>, ident: &Ident<'_>, dialect: &dyn Dialect) -> std::fmt::Result {
+    let Some(quote) = ident.quote_style else {
+        return write!(f, "{}", ident.value);
+    };
+    let quote = if dialect.is_identifier_quote(quote) {
+        quote
+    } else {
+        ['"', '`', '['].into_iter().find(|&quote| dialect.is_identifier_quote(quote)).unwrap_or('"')
+    };
+    write!(f, "{}", Ident::with_quote(quote, ident.value.as_ref()))
+}
+
+/// Renders the expression as SQL that parses back into the same tree. Parentheses are only added where the operator precedence requires them, so `(a + b) * c` keeps its parentheses while `a + (b * c)` is written as `a + b * c`. Strings are written in single quotes with doubled quotes, and backslashes are doubled as well in dialects with backslash escapes.
+impl Display for Sql<'_, Expression<'_>> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        let dialect = self.dialect;
+        match self.node {
+            Expression::BinaryOperation { left_operand, operator, right_operand, .. } => {
+                let precedence = operator.precedence();
+                write_operand(f, left_operand, dialect, left_operand.precedence() < precedence)?;
+                write!(f, " {} ", operator)?;
+                write_operand(f, right_operand, dialect, right_operand.precedence() <= precedence)
+            }
+            Expression::UnaryOperation { operand, operator: operator @ (UnaryOperator::Asc | UnaryOperator::Desc), .. } => {
+                write!(f, "{} {}", operand.sql(dialect), operator)
+            }
+            Expression::UnaryOperation { operand, operator, .. } => {
+                match (operator, operand.as_ref()) {
+                    (UnaryOperator::Not, _) => write!(f, "NOT ")?,
+                    // `--` would start a comment
+                    (UnaryOperator::Minus, Expression::UnaryOperation { operator: UnaryOperator::Minus, .. }) => write!(f, "- ")?,
+                    _ => write!(f, "{}", operator)?,
+                }
+                write_operand(f, operand, dialect, operand.precedence() < operator.precedence())
+            }
+            Expression::Cast { expression, data_type, .. } => {
+                write_operand(f, expression, dialect, expression.precedence() < CAST_PRECEDENCE)?;
+                write!(f, "::{}", data_type.sql(dialect))
+            }
+            Expression::Number(num, _) => write!(f, "{num}"),
+            Expression::Decimal(num, _) => write!(f, "{num}"),
+            Expression::HexString(digits, _) => write!(f, "X'{digits}'"),
+            Expression::BitString(digits, _) => write!(f, "B'{digits}'"),
+            Expression::Identifier(iden, _) => write_ident(f, iden, dialect),
+            Expression::String(str, _) if dialect.supports_backslash_escapes() => write!(f, "'{}'", str.replace('\\', "\\\\").replace('\'', "''")),
+            Expression::String(str, _) => write!(f, "'{}'", str.replace('\'', "''")),
+            Expression::Bool(b, _) => write!(f, "{}", if *b { "TRUE" } else { "FALSE" }),
+        }
+    }
+}
+
+/// Renders the statement as canonical SQL for the dialect, terminated with `;`: keywords in upper case, single spaces and the minimal parentheses, for example `SELECT id, age * 2 FROM users WHERE age >= 18 ORDER BY id DESC;`. A parser for the same dialect reads it back into an equal statement.
+impl Display for Sql<'_, Statement<'_>> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        let dialect = self.dialect;
+        match self.node {
+            Statement::Select { columns, from, r#where, orderby, .. } => {
+                write!(f, "SELECT ")?;
+                write_list(f, columns, |f, column| write!(f, "{}", column.sql(dialect)))?;
+                write!(f, " FROM ")?;
+                write_ident(f, from, dialect)?;
+                if let Some(condition) = r#where {
+                    write!(f, " WHERE {}", condition.sql(dialect))?;
+                }
+                if !orderby.is_empty() {
+                    write!(f, " ORDER BY ")?;
+                    write_list(f, orderby, |f, item| write!(f, "{}", item.sql(dialect)))?;
+                }
+            }
+            Statement::CreateTable { table_name, column_list, .. } => {
+                write!(f, "CREATE TABLE ")?;
+                write_ident(f, table_name, dialect)?;
+                write!(f, " (")?;
+                write_list(f, column_list, |f, column| write!(f, "{}", column.sql(dialect)))?;
+                write!(f, ")")?;
+            }
+        }
+        write!(f, ";")
+    }
+}
+
+impl Display for Sql<'_, TableColumn<'_>> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write_ident(f, &self.node.column_name, self.dialect)?;
+        write!(f, " {}", self.node.column_type.sql(self.dialect))?;
+        for constraint in &self.node.constraints {
+            write!(f, " {}", constraint.sql(self.dialect))?;
+        }
+        Ok(())
+    }
+}
+
+impl Display for Sql<'_, Ident<'_>> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write_ident(f, self.node, self.dialect)
+    }
+}
+
+impl Display for Sql<'_, DBType> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self.node {
+            DBType::Int => write!(f, "INT"),
+            DBType::Varchar(size) => write!(f, "VARCHAR({})", size),
+            DBType::Bool => write!(f, "BOOL"),
+            DBType::Blob if self.dialect.is_keyword(&Keyword::Blob) => write!(f, "BLOB"),
+            DBType::Blob => write!(f, "BYTEA"),
+        }
+    }
+}
+
+impl Display for Sql<'_, Constraint<'_>> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self.node {
+            Constraint::NotNull(_) => write!(f, "NOT NULL"),
+            Constraint::PrimaryKey(_) => write!(f, "PRIMARY KEY"),
+            Constraint::Check(expression, _) => write!(f, "CHECK ({})", expression.sql(self.dialect)),
+        }
+    }
+}
+
+/// Renders the expression for the generic dialect, see `Expression::sql`.
+impl Display for Expression<'_> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.sql(&GenericDialect))
+    }
+}
+
+/// Renders the statement for the generic dialect (`BLOB`, `::` casts, `"quoted"` identifiers and single-quoted strings without backslash escapes), which `parse_sql` reads back into an equal statement. Use `Statement::sql` to render for another dialect.
+impl Display for Statement<'_> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.sql(&GenericDialect))
+    }
+}
+
+impl Display for TableColumn<'_> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.sql(&GenericDialect))
+    }
+}
+
+impl Display for DBType {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.sql(&GenericDialect))
+    }
+}
+
+impl Display for Constraint<'_> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.sql(&GenericDialect))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::dialect::{GenericDialect, MySqlDialect, PostgresDialect, SqliteDialect};
+    use crate::ident::Ident;
+    use crate::parser::Parser;
+
+    fn round_trip(input: &str) -> String {
+        round_trip_in(input, &GenericDialect)
+    }
+
+    fn round_trip_in(input: &str, dialect: &dyn Dialect) -> String {
+        let statement = Parser::new(input, dialect).parse_statement().unwrap();
+        let rendered = statement.sql(dialect).to_string();
+        let reparsed = Parser::new(&rendered, dialect).parse_statement().unwrap();
+        assert_eq!(reparsed, statement, "{} rendered as {}", input, rendered);
+        rendered
+    }
+
+    fn expression(input: &str) -> String {
+        let rendered = round_trip(&format!("SELECT {} FROM t;", input));
+        rendered["SELECT ".len()..rendered.len() - " FROM t;".len()].to_string()
+    }
+
+    #[test]
+    fn test_render_statements() {
+        assert_eq!(
+            round_trip("select  id,age*2 from Users where (age >= 18) and name = 'O''Brien' order by id desc, age;"),
+            "SELECT id, age * 2 FROM Users WHERE age >= 18 AND name = 'O''Brien' ORDER BY id DESC, age;"
+        );
+        assert_eq!(
+            round_trip("CREATE TABLE t(id INT PRIMARY KEY, name varchar(10) NOT NULL, data bytea, ok BOOL CHECK(ok OR NOT ok));"),
+            "CREATE TABLE t (id INT PRIMARY KEY, name VARCHAR(10) NOT NULL, data BLOB, ok BOOL CHECK (ok OR NOT ok));"
+        );
+        assert_eq!(round_trip(r#"SELECT * FROM "my table";"#), r#"SELECT * FROM "my table";"#);
+    }
+
+    #[test]
+    fn test_minimal_parentheses() {
+        assert_eq!(expression("(a + b) * c"), "(a + b) * c");
+        assert_eq!(expression("a + (b * c)"), "a + b * c");
+        assert_eq!(expression("(a - b) - c"), "a - b - c");
+        assert_eq!(expression("a - (b - c)"), "a - (b - c)");
+        assert_eq!(expression("NOT (a = b)"), "NOT (a = b)");
+        assert_eq!(expression("(NOT a) = b"), "NOT a = b");
+        assert_eq!(expression("-(-1)"), "- -1");
+        assert_eq!(expression("1 - (-1)"), "1 - -1");
+        assert_eq!(expression("(-1)::INT"), "(-1)::INT");
+        assert_eq!(expression("-(1::VARCHAR(3))"), "-1::VARCHAR(3)");
+        assert_eq!(expression("(a || b)::BLOB"), "(a || b)::BLOB");
+        assert_eq!(expression("(a <> b) OR (c % 2 = 0)"), "a != b OR c % 2 = 0");
+        assert_eq!(expression("x'ff', b'01', 1.5e3, true"), "X'ff', B'01', 1.5e3, TRUE");
+    }
+
+    #[test]
+    fn test_operator_combinations() {
+        let operators = [
+            BinaryOperator::Plus,
+            BinaryOperator::Minus,
+            BinaryOperator::Multiply,
+            BinaryOperator::Divide,
+            BinaryOperator::Modulo,
+            BinaryOperator::Concat,
+            BinaryOperator::GreaterThan,
+            BinaryOperator::LessThanOrEqual,
+            BinaryOperator::Equal,
+            BinaryOperator::NotEqual,
+            BinaryOperator::And,
+            BinaryOperator::Or,
+        ];
+        let leaf = |name: &'static str| Expression::Identifier(Ident::new(name), Span::default());
+        let binary = |left, operator, right| Expression::BinaryOperation {
+            left_operand: Box::new(left),
+            operator,
+            right_operand: Box::new(right),
+            span: Span::default(),
+        };
+        let unary = |operator, operand| Expression::UnaryOperation { operand: Box::new(operand), operator, span: Span::default() };
+
+        for outer in &operators {
+            for inner in &operators {
+                let nested = [
+                    binary(binary(leaf("a"), inner.clone(), leaf("b")), outer.clone(), leaf("c")),
+                    binary(leaf("a"), outer.clone(), binary(leaf("b"), inner.clone(), leaf("c"))),
+                    unary(UnaryOperator::Not, binary(leaf("a"), inner.clone(), leaf("b"))),
+                    binary(unary(UnaryOperator::Minus, leaf("a")), outer.clone(), unary(UnaryOperator::Minus, leaf("b"))),
+                    Expression::Cast { expression: Box::new(binary(leaf("a"), inner.clone(), leaf("b"))), data_type: DBType::Int, span: Span::default() },
+                ];
+                for expression in nested {
+                    let statement = Statement::Select {
+                        columns: vec![expression],
+                        from: Ident::new("t"),
+                        r#where: None,
+                        orderby: vec![],
+                        span: Span::default(),
+                    };
+                    let rendered = statement.to_string();
+                    let reparsed = Parser::new(&rendered, &GenericDialect).parse_statement();
+                    assert_eq!(reparsed.as_ref().ok(), Some(&statement), "{}", rendered);
+                }
             }
-            Expression::UnaryOperation { operand, operator } => {
-                write!(f, "({:?} {:?})", operator, operand)
+        }
+    }
+
+    #[test]
+    fn test_render_quoted_identifiers() {
+        let input = "SELECT `order`, [a]]b], \"c\" FROM [t];";
+        assert_eq!(round_trip_in(input, &SqliteDialect), input);
+        let statement = Parser::new(input, &SqliteDialect).parse_statement().unwrap();
+        assert_eq!(statement.to_string(), r#"SELECT "order", "a]b", "c" FROM "t";"#);
+        assert_eq!(round_trip(&statement.to_string()), statement.to_string());
+
+        let input = "SELECT `a``b` FROM `t` WHERE c = 'x';";
+        assert_eq!(round_trip_in(input, &MySqlDialect), input);
+        let statement = Parser::new(input, &MySqlDialect).parse_statement().unwrap();
+        assert_eq!(statement.to_string(), r#"SELECT "a`b" FROM "t" WHERE c = 'x';"#);
+        // Only the quote character changes, so the generic statement renders back into the MySQL input.
+        let rendered = statement.to_string();
+        let generic = Parser::new(&rendered, &GenericDialect).parse_statement().unwrap();
+        assert_eq!(generic.sql(&MySqlDialect).to_string(), input);
+
+        let statement = Parser::new(r#"SELECT "a""b" FROM t;"#, &PostgresDialect).parse_statement().unwrap();
+        assert_eq!(statement.sql(&MySqlDialect).to_string(), "SELECT `a\"b` FROM t;");
+    }
+
+    #[test]
+    fn test_render_dialect_strings_and_types() {
+        assert_eq!(round_trip_in(r"SELECT 'a\\b', 'a\\', 'it\'s', 'tab\t' FROM t;", &MySqlDialect), "SELECT 'a\\\\b', 'a\\\\', 'it''s', 'tab\t' FROM t;");
+        assert_eq!(round_trip_in(r"SELECT E'a\\b', 'c\d' FROM t;", &PostgresDialect), r"SELECT 'a\b', 'c\d' FROM t;");
+        assert_eq!(round_trip(r"SELECT 'a\b' FROM t;"), r"SELECT 'a\b' FROM t;");
+
+        assert_eq!(round_trip_in("SELECT a::BYTEA FROM t;", &PostgresDialect), "SELECT a::BYTEA FROM t;");
+        assert_eq!(
+            round_trip_in("create table files (id int primary key, data bytea not null check (data::bytea != ''));", &PostgresDialect),
+            "CREATE TABLE files (id INT PRIMARY KEY, data BYTEA NOT NULL CHECK (data::BYTEA != ''));"
+        );
+        assert_eq!(round_trip_in("CREATE TABLE t (data BLOB);", &SqliteDialect), "CREATE TABLE t (data BLOB);");
+        assert_eq!(round_trip_in("CREATE TABLE t (data BLOB);", &MySqlDialect), "CREATE TABLE t (data BLOB);");
+    }
+
+    #[test]
+    fn test_render_round_trips_in_every_dialect() {
+        let dialects: [&dyn Dialect; 4] = [&GenericDialect, &PostgresDialect, &MySqlDialect, &SqliteDialect];
+        let inputs = [
+            "SELECT id, -age * 2, 'O''Brien', 9.99, X'ff', TRUE FROM Users WHERE (age >= 18 OR age < 5) AND NOT name = 'x' ORDER BY id DESC, age;",
+            "CREATE TABLE t (id INT PRIMARY KEY, name VARCHAR(10) NOT NULL, ok BOOL CHECK (ok OR NOT ok));",
+            "SELECT a || b, a <> b, a % 2 FROM t;",
+        ];
+        for dialect in dialects {
+            for input in inputs {
+                round_trip_in(input, dialect);
             }
-            Expression::Number(num) => write!(f, "{num}"),
-            Expression::Identifier(iden) => write!(f, "{}", iden),
-            Expression::String(str) => write!(f, "\"{}\"", str),
-            Expression::Bool(b) => write!(f, "{}", b)
         }
     }
-}
\ No newline at end of file
+}
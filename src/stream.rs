@@ -0,0 +1,330 @@
+use std::io::{self, BufRead};
+use std::mem;
+use std::str;
+
+use crate::dialect::Dialect;
+use crate::error::ReadError;
+use crate::ident::closing_quote;
+use crate::parser::Parser;
+use crate::span::Span;
+use crate::statement::Statement;
+use crate::tokenizer::Tokenizer;
+
+/// Parses statements one at a time from any `BufRead`, for inputs too large to hold in memory as one `&str`, such as multi-gigabyte `pg_dump` or `mysqldump` files.
+///
+/// The reader scans the input chunk by chunk for the `;` that ends the next statement, keeping track of strings, quoted identifiers and comments across chunk boundaries, so a `;` inside `'a;b'` or `/* ; */` does not end a statement even when the chunk ends in the middle of it. Once a statement is complete, its text is tokenized and parsed on its own and the buffer is reused for the next one, so memory use is bounded by the longest statement rather than the size of the input.
+///
+/// Every item is an owned `Statement<'static>`. Spans, line and column numbers and statement indexes refer to the whole input, as if it had been parsed in one piece. A statement that fails to parse is yielded as `ReadError::Parse` and reading carries on with the next one; an I/O error (including input that is not valid UTF-8) is yielded as `ReadError::Io` and ends the iteration.
+pub struct StatementReader<'d, R> {
+    reader: R,
+    dialect: &'d dyn Dialect,
+    scanner: Scanner,
+    /// The text of the statement being read, up to and including its `;`.
+    buffer: Vec<u8>,
+    /// The position in the whole input where `buffer` starts.
+    location: Span,
+    statement_index: usize,
+    done: bool,
+}
+
+impl<'d, R: BufRead> StatementReader<'d, R> {
+    /// Creates a reader for SQL written in `dialect`. Wrap a `File` in a `BufReader` first.
+    pub fn new(reader: R, dialect: &'d dyn Dialect) -> Self {
+        Self {
+            reader,
+            dialect,
+            scanner: Scanner::default(),
+            buffer: Vec::new(),
+            location: Span::new(0, 0, 1, 1),
+            statement_index: 0,
+            done: false,
+        }
+    }
+
+    /// Reads the text of the next statement into `buffer`, up to and including its `;`, or up to the end of the input for a last statement without one. Returns `false` if the input is exhausted and nothing was read.
+    fn fill_statement(&mut self) -> io::Result<bool> {
+        loop {
+            let chunk = match self.reader.fill_buf() {
+                Ok(chunk) => chunk,
+                Err(error) if error.kind() == io::ErrorKind::Interrupted => continue,
+                Err(error) => return Err(error),
+            };
+            if chunk.is_empty() {
+                return Ok(!self.buffer.is_empty());
+            }
+
+            let end = chunk.iter().position(|&byte| self.scanner.feed(byte, self.dialect));
+            let used = end.map_or(chunk.len(), |end| end + 1);
+            self.buffer.extend_from_slice(&chunk[..used]);
+            self.reader.consume(used);
+            if end.is_some() {
+                return Ok(true);
+            }
+        }
+    }
+
+    /// Parses the statement text in `text`. Returns `None` if it only contained whitespace, comments or an empty statement.
+    fn parse(&mut self, text: &[u8]) -> Option<Result<Statement<'static>, ReadError>> {
+        let text = match str::from_utf8(text) {
+            Ok(text) => text,
+            Err(error) => {
+                self.done = true;
+                return Some(Err(io::Error::new(io::ErrorKind::InvalidData, error).into()));
+            },
+        };
+
+        let start = self.location;
+        self.location = Self::location_after(start, text);
+
+        let tokenizer = Tokenizer::new(text, self.dialect).starting_at(start);
+        let statement = match Parser::from_tokenizer(tokenizer).parse_statements() {
+            Ok(statements) => Ok(statements.into_iter().next()?.into_owned()),
+            Err(error) => Err(error.with_statement_index(self.statement_index).into()),
+        };
+        self.statement_index += 1;
+        Some(statement)
+    }
+
+    /// Returns the position right after `text`, which starts at `start`.
+    fn location_after(start: Span, text: &str) -> Span {
+        let offset = start.start + text.len();
+        match text.rfind('\n') {
+            Some(newline) => {
+                let line = start.line + text.matches('\n').count();
+                Span::new(offset, offset, line, text[newline + 1..].chars().count() + 1)
+            },
+            None => Span::new(offset, offset, start.line, start.column + text.chars().count()),
+        }
+    }
+}
+
+impl<R: BufRead> Iterator for StatementReader<'_, R> {
+    type Item = Result<Statement<'static>, ReadError>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        while !self.done {
+            match self.fill_statement() {
+                Ok(true) => {},
+                Ok(false) => self.done = true,
+                Err(error) => {
+                    self.done = true;
+                    return Some(Err(error.into()));
+                },
+            }
+
+            let buffer = mem::take(&mut self.buffer);
+            let statement = self.parse(&buffer);
+            self.buffer = buffer;
+            self.buffer.clear();
+            if statement.is_some() {
+                return statement;
+            }
+        }
+        None
+    }
+}
+
+/// What the scanner is in the middle of. Only the delimiters matter, and they are all ASCII, so the input is scanned byte by byte and a UTF-8 character split across two chunks is never a problem.
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+enum State {
+    #[default]
+    Code,
+    /// A `-` that may start a `--` comment.
+    Dash,
+    /// A `/` that may start a `/*` comment.
+    Slash,
+    LineComment,
+    /// A block comment nested `depth` levels deep; `last` is the previous byte, to spot `/*` and `*/`.
+    BlockComment { depth: usize, last: u8 },
+    /// A string literal or a quoted identifier that ends with `close`.
+    Quoted { close: u8, backslash_escapes: bool },
+    /// The byte after a backslash in a string with backslash escapes.
+    Escape { close: u8 },
+    /// A closing quote, which is the first half of an escaped quote if another one follows.
+    QuoteEnd { close: u8, backslash_escapes: bool },
+}
+
+/// Finds the `;` that ends a statement, following the same quoting and comment rules as `Tokenizer` so that a `;` inside a string, a quoted identifier or a comment is skipped.
+#[derive(Debug, Default)]
+struct Scanner {
+    state: State,
+    /// The length of the identifier that ends at `last`, used to recognize the `E` of an `E'...'` string.
+    word: usize,
+    last: u8,
+}
+
+impl Scanner {
+    /// Feeds the next byte of the input. Returns `true` if it is a `;` that ends a statement.
+    fn feed(&mut self, byte: u8, dialect: &dyn Dialect) -> bool {
+        match self.state {
+            State::Code => {},
+            State::Dash | State::Slash => {
+                let comment = match (self.state, byte) {
+                    (State::Dash, b'-') => State::LineComment,
+                    (State::Slash, b'*') => State::BlockComment { depth: 1, last: 0 },
+                    _ => State::Code,
+                };
+                self.state = comment;
+                if comment != State::Code {
+                    return false;
+                }
+            },
+            State::LineComment => {
+                if byte == b'\n' {
+                    self.state = State::Code;
+                }
+                return false;
+            },
+            State::BlockComment { depth, last } => {
+                self.state = match (last, byte) {
+                    (b'/', b'*') => State::BlockComment { depth: depth + 1, last: 0 },
+                    (b'*', b'/') if depth == 1 => State::Code,
+                    (b'*', b'/') => State::BlockComment { depth: depth - 1, last: 0 },
+                    _ => State::BlockComment { depth, last: byte },
+                };
+                return false;
+            },
+            State::Quoted { close, backslash_escapes } => {
+                if byte == close {
+                    self.state = State::QuoteEnd { close, backslash_escapes };
+                } else if byte == b'\\' && backslash_escapes {
+                    self.state = State::Escape { close };
+                }
+                return false;
+            },
+            State::Escape { close } => {
+                self.state = State::Quoted { close, backslash_escapes: true };
+                return false;
+            },
+            State::QuoteEnd { close, backslash_escapes } => {
+                if byte == close {
+                    self.state = State::Quoted { close, backslash_escapes };
+                    return false;
+                }
+                self.state = State::Code;
+            },
+        }
+
+        let c = char::from(byte);
+        let ends_statement = match byte {
+            b';' => true,
+            b'-' => {
+                self.state = State::Dash;
+                false
+            },
+            b'/' => {
+                self.state = State::Slash;
+                false
+            },
+            _ if byte.is_ascii() && dialect.is_identifier_quote(c) => {
+                self.state = State::Quoted { close: closing_quote(c) as u8, backslash_escapes: false };
+                false
+            },
+            _ if byte.is_ascii() && dialect.is_string_quote(c) => {
+                let escape_string = byte == b'\'' && self.word == 1 && matches!(self.last, b'e' | b'E') && dialect.supports_escape_strings();
+                self.state = State::Quoted { close: byte, backslash_escapes: escape_string || dialect.supports_backslash_escapes() };
+                false
+            },
+            _ => false,
+        };
+
+        // Digits continue an identifier but do not start one, so the `e` in `1e'...'` starts a new word.
+        self.word = match byte {
+            b'0'..=b'9' if self.word > 0 => self.word + 1,
+            b'a'..=b'z' | b'A'..=b'Z' | b'_' | 0x80.. => self.word + 1,
+            _ => 0,
+        };
+        self.last = byte;
+        ends_statement
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::dialect::{GenericDialect, MySqlDialect, PostgresDialect};
+    use crate::error::ParseErrorKind;
+    use crate::ident::Ident;
+    use crate::statement::Expression;
+    use std::io::{BufReader, Read};
+
+    /// A reader that returns at most `chunk` bytes per read, so every boundary case gets exercised.
+    struct Trickle<'a> {
+        data: &'a [u8],
+        chunk: usize,
+    }
+
+    impl Read for Trickle<'_> {
+        fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+            let n = self.chunk.min(buf.len()).min(self.data.len());
+            buf[..n].copy_from_slice(&self.data[..n]);
+            self.data = &self.data[n..];
+            Ok(n)
+        }
+    }
+
+    fn read_all(input: &str, dialect: &dyn Dialect, chunk: usize) -> Vec<Result<Statement<'static>, ReadError>> {
+        let reader = BufReader::with_capacity(chunk, Trickle { data: input.as_bytes(), chunk });
+        StatementReader::new(reader, dialect).collect()
+    }
+
+    #[test]
+    fn test_matches_parse_statements() {
+        let input = "CREATE TABLE t(name VARCHAR(10) CHECK (name != 'a;b'), \"x;y\" INT);\n\
+                     -- a comment; with a semicolon\n\
+                     SELECT /* ; /* nested ; */ */ name, 'it''s;' FROM t WHERE name = E'\\';';;\n\
+                     SELECT ąžuolas FROM äpfel;";
+        let expected = Parser::new(input, &GenericDialect).parse_statements().unwrap();
+        assert_eq!(expected.len(), 3);
+
+        for chunk in [1, 2, 3, 7, 64, 4096] {
+            let statements: Vec<_> = read_all(input, &GenericDialect, chunk).into_iter().map(Result::unwrap).collect();
+            assert_eq!(statements, expected, "chunk size {}", chunk);
+        }
+    }
+
+    #[test]
+    fn test_dialect_quotes() {
+        let input = r#"SELECT `a;b` FROM t WHERE c = "x\";y";"#;
+        let statements = read_all(input, &MySqlDialect, 1);
+        assert_eq!(statements.len(), 1);
+        let Ok(Statement::Select { columns, .. }) = &statements[0] else {
+            panic!("expected a SELECT statement, found {:?}", statements[0]);
+        };
+        assert_eq!(columns, &vec![Expression::Identifier(Ident::with_quote('`', "a;b"), Span::default())]);
+
+        // Without backslash escapes, `\'` ends the string and the `;` after it ends the statement.
+        let statements = read_all(r"SELECT 'a\'; SELECT b FROM c;", &PostgresDialect, 3);
+        assert_eq!(statements.len(), 2);
+    }
+
+    #[test]
+    fn test_errors_and_positions() {
+        let input = "SELECT a FROM t;\nSELECT FROM t;\n  SELECT b FROM t;\nSELECT 'open";
+        let results = read_all(input, &GenericDialect, 5);
+        assert_eq!(results.len(), 4);
+        assert!(results[0].is_ok());
+        assert!(results[2].is_ok());
+        assert_eq!(results[2].as_ref().unwrap().span().range(), 34..50);
+        assert_eq!((results[2].as_ref().unwrap().span().line, results[2].as_ref().unwrap().span().column), (3, 3));
+
+        let Err(ReadError::Parse(error)) = &results[1] else {
+            panic!("expected a parse error, found {:?}", results[1]);
+        };
+        assert_eq!((error.span().line, error.span().column, error.statement_index()), (2, 8, Some(1)));
+
+        let Err(ReadError::Parse(error)) = &results[3] else {
+            panic!("expected a parse error, found {:?}", results[3]);
+        };
+        assert_eq!(error.kind(), &ParseErrorKind::UnterminatedString);
+        assert_eq!((error.span().line, error.span().column), (4, 8));
+    }
+
+    #[test]
+    fn test_invalid_utf8() {
+        let reader: &[u8] = b"SELECT a FROM t;\nSELECT \xff FROM t;\nSELECT b FROM t;";
+        let results: Vec<_> = StatementReader::new(reader, &GenericDialect).collect();
+        assert_eq!(results.len(), 2);
+        assert!(matches!(&results[1], Err(ReadError::Io(error)) if error.kind() == io::ErrorKind::InvalidData));
+    }
+}
@@ -1,12 +1,25 @@
+use std::borrow::Cow;
 use std::fmt::{Debug, Display, Formatter};
 
+use crate::ident::Ident;
+
+/// A token of the input. Text carrying tokens borrow from the input (`Cow::Borrowed`) and only own their text when it had to be rewritten, such as a string with a doubled quote or an escape sequence; `into_owned` detaches a token from the input.
 #[derive(PartialEq, Clone, Debug)]
-pub enum Token {
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize), serde(tag = "type", content = "value"))]
+pub enum Token<'a> {
     Keyword(Keyword),
-    Identifier(String),
-    String(String),
+    Identifier(Ident<'a>),
+    String(Cow<'a, str>),
     Number(u64),
-    Invalid(char),
+    /// A numeric literal that does not fit `Number`: one with a fractional part or an exponent (`9.99`, `.5`, `1e6`), or a whole number larger than `u64::MAX`. It is kept exactly as written.
+    Decimal(Cow<'a, str>),
+    /// The hex digits of an `X'DEADBEEF'` or `0xFF` literal, as written.
+    HexString(Cow<'a, str>),
+    /// The binary digits of a `B'1010'` literal, as written.
+    BitString(Cow<'a, str>),
+    Comment(Cow<'a, str>),
+    /// A run of whitespace, only produced with `TokenizerOptions::emit_whitespace`.
+    Whitespace(Cow<'a, str>),
     RightParentheses,
     LeftParentheses,
     GreaterThan,
@@ -21,10 +34,16 @@ pub enum Token {
     Plus,
     Comma,
     Semicolon,
+    Percent,
+    /// `||`, string concatenation or logical `OR` depending on the dialect.
+    DoublePipe,
+    /// `::`, the PostgreSQL type cast.
+    DoubleColon,
     Eof,
 }
 
 #[derive(PartialEq, Clone, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum Keyword {
     Select,
     Create,
@@ -47,17 +66,117 @@ pub enum Keyword {
     Bool,
     Varchar,
     Null,
+    Blob,
+    Bytea,
+}
+
+impl Keyword {
+    /// Looks up the keyword spelled `word`, ignoring ASCII case. Non-ASCII letters never match, so `ſelect` is not `SELECT`. The word is upper-cased into a small stack buffer, so the lookup does not allocate.
+    pub fn lookup(word: &str) -> Option<Keyword> {
+        let mut buffer = [0u8; 8];
+        let upper = buffer.get_mut(..word.len())?;
+        upper.copy_from_slice(word.as_bytes());
+        upper.make_ascii_uppercase();
+
+        let keyword = match &*upper {
+            b"SELECT" => Keyword::Select,
+            b"CREATE" => Keyword::Create,
+            b"TABLE" => Keyword::Table,
+            b"WHERE" => Keyword::Where,
+            b"ORDER" => Keyword::Order,
+            b"BY" => Keyword::By,
+            b"ASC" => Keyword::Asc,
+            b"DESC" => Keyword::Desc,
+            b"FROM" => Keyword::From,
+            b"AND" => Keyword::And,
+            b"OR" => Keyword::Or,
+            b"NOT" => Keyword::Not,
+            b"TRUE" => Keyword::True,
+            b"FALSE" => Keyword::False,
+            b"PRIMARY" => Keyword::Primary,
+            b"KEY" => Keyword::Key,
+            b"CHECK" => Keyword::Check,
+            b"INT" => Keyword::Int,
+            b"BOOL" => Keyword::Bool,
+            b"VARCHAR" => Keyword::Varchar,
+            b"NULL" => Keyword::Null,
+            b"BLOB" => Keyword::Blob,
+            b"BYTEA" => Keyword::Bytea,
+            _ => return None,
+        };
+        Some(keyword)
+    }
+
+    /// Returns `true` for keywords that would make the grammar ambiguous if they were used as identifiers: statement and clause keywords, `AND`, `OR`, `NOT` and the literals `TRUE`, `FALSE` and `NULL`. The others (`ASC`, `DESC`, `KEY`, `PRIMARY`, `CHECK` and the type names) are only keywords where the grammar expects them and are read as identifiers everywhere else.
+    pub fn is_reserved(&self) -> bool {
+        matches!(
+            self,
+            Keyword::Select
+                | Keyword::Create
+                | Keyword::Table
+                | Keyword::Where
+                | Keyword::Order
+                | Keyword::By
+                | Keyword::From
+                | Keyword::And
+                | Keyword::Or
+                | Keyword::Not
+                | Keyword::True
+                | Keyword::False
+                | Keyword::Null
+        )
+    }
+}
+
+impl Token<'_> {
+    /// Copies any text borrowed from the input, so the token can outlive it.
+    pub fn into_owned(self) -> Token<'static> {
+        match self {
+            Token::Keyword(keyword) => Token::Keyword(keyword),
+            Token::Identifier(ident) => Token::Identifier(ident.into_owned()),
+            Token::String(string) => Token::String(Cow::Owned(string.into_owned())),
+            Token::Number(number) => Token::Number(number),
+            Token::Decimal(number) => Token::Decimal(Cow::Owned(number.into_owned())),
+            Token::HexString(digits) => Token::HexString(Cow::Owned(digits.into_owned())),
+            Token::BitString(digits) => Token::BitString(Cow::Owned(digits.into_owned())),
+            Token::Comment(comment) => Token::Comment(Cow::Owned(comment.into_owned())),
+            Token::Whitespace(whitespace) => Token::Whitespace(Cow::Owned(whitespace.into_owned())),
+            Token::RightParentheses => Token::RightParentheses,
+            Token::LeftParentheses => Token::LeftParentheses,
+            Token::GreaterThan => Token::GreaterThan,
+            Token::GreaterThanOrEqual => Token::GreaterThanOrEqual,
+            Token::LessThan => Token::LessThan,
+            Token::LessThanOrEqual => Token::LessThanOrEqual,
+            Token::Equal => Token::Equal,
+            Token::NotEqual => Token::NotEqual,
+            Token::Star => Token::Star,
+            Token::Divide => Token::Divide,
+            Token::Minus => Token::Minus,
+            Token::Plus => Token::Plus,
+            Token::Comma => Token::Comma,
+            Token::Semicolon => Token::Semicolon,
+            Token::Percent => Token::Percent,
+            Token::DoublePipe => Token::DoublePipe,
+            Token::DoubleColon => Token::DoubleColon,
+            Token::Eof => Token::Eof,
+        }
+    }
 }
 
-impl Display for Token {
+impl Display for Token<'_> {
     fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
         match self {
             Token::Keyword(keyword) => write!(f, "{}", keyword),
-            Token::Identifier(iden) => write!(f, "{:?}", iden),
+            Token::Identifier(iden) => write!(f, "{:?}", iden.value),
             Token::String(str) => write!(f, "{:?}", str),
             Token::Number(num) => write!(f, "{:?}", num),
-            Token::RightParentheses => write!(f, "("),
-            Token::LeftParentheses => write!(f, ")"),
+            Token::Decimal(num) => write!(f, "{}", num),
+            Token::HexString(digits) => write!(f, "X'{}'", digits),
+            Token::BitString(digits) => write!(f, "B'{}'", digits),
+            Token::Comment(comment) => write!(f, "{}", comment),
+            Token::Whitespace(whitespace) => write!(f, "{}", whitespace),
+            Token::RightParentheses => write!(f, ")"),
+            Token::LeftParentheses => write!(f, "("),
             Token::GreaterThan => write!(f, ">"),
             Token::GreaterThanOrEqual => write!(f, ">="),
             Token::LessThan => write!(f, "<"),
@@ -70,8 +189,10 @@ impl Display for Token {
             Token::Plus => write!(f, "+"),
             Token::Comma => write!(f, ","),
             Token::Semicolon => write!(f, ";"),
+            Token::Percent => write!(f, "%"),
+            Token::DoublePipe => write!(f, "||"),
+            Token::DoubleColon => write!(f, "::"),
             Token::Eof => write!(f, "Eof"),
-            Token::Invalid(c) => write!(f, "{}", c),
         }
     }
 }
@@ -79,27 +200,29 @@ impl Display for Token {
 impl Display for Keyword {
     fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
         match self {
-            Keyword::Select => write!(f, "Select"),
-            Keyword::Create => write!(f, "Create"),
-            Keyword::Table => write!(f, "Table"),
-            Keyword::Where => write!(f, "Where"),
-            Keyword::Order => write!(f, "Order"),
-            Keyword::By => write!(f, "By"),
-            Keyword::Asc => write!(f, "Asc"),
-            Keyword::Desc => write!(f, "Desc"),
-            Keyword::From => write!(f, "From"),
-            Keyword::And => write!(f, "And"),
-            Keyword::Or => write!(f, "Or"),
-            Keyword::Not => write!(f, "Not"),
-            Keyword::True => write!(f, "True"),
-            Keyword::False => write!(f, "False"),
-            Keyword::Primary => write!(f, "Primary"),
-            Keyword::Key => write!(f, "Key"),
-            Keyword::Check => write!(f, "Check"),
-            Keyword::Int => write!(f, "Int"),
-            Keyword::Bool => write!(f, "Bool"),
-            Keyword::Varchar => write!(f, "Varchar"),
-            Keyword::Null => write!(f, "Null"),
+            Keyword::Select => write!(f, "SELECT"),
+            Keyword::Create => write!(f, "CREATE"),
+            Keyword::Table => write!(f, "TABLE"),
+            Keyword::Where => write!(f, "WHERE"),
+            Keyword::Order => write!(f, "ORDER"),
+            Keyword::By => write!(f, "BY"),
+            Keyword::Asc => write!(f, "ASC"),
+            Keyword::Desc => write!(f, "DESC"),
+            Keyword::From => write!(f, "FROM"),
+            Keyword::And => write!(f, "AND"),
+            Keyword::Or => write!(f, "OR"),
+            Keyword::Not => write!(f, "NOT"),
+            Keyword::True => write!(f, "TRUE"),
+            Keyword::False => write!(f, "FALSE"),
+            Keyword::Primary => write!(f, "PRIMARY"),
+            Keyword::Key => write!(f, "KEY"),
+            Keyword::Check => write!(f, "CHECK"),
+            Keyword::Int => write!(f, "INT"),
+            Keyword::Bool => write!(f, "BOOL"),
+            Keyword::Varchar => write!(f, "VARCHAR"),
+            Keyword::Null => write!(f, "NULL"),
+            Keyword::Blob => write!(f, "BLOB"),
+            Keyword::Bytea => write!(f, "BYTEA"),
         }
     }
 }
\ No newline at end of file
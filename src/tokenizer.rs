@@ -1,192 +1,733 @@
+use crate::dialect::Dialect;
+use crate::error::{ParseError, ParseErrorKind};
+use crate::ident::{closing_quote, CaseFolding, Ident};
+use crate::span::Span;
 use crate::token::{Keyword, Token};
+use std::borrow::Cow;
 use std::iter::Peekable;
 use std::str::Chars;
+use unicode_ident::{is_xid_continue, is_xid_start};
 
+/// Splits the input into tokens. Every token is yielded together with the `Span` it was read from; the position is tracked as the input is consumed (`offset` in bytes, `line` and `column` in characters, both 1-based). Quoting, escaping, keywords and the optional operators follow the `Dialect` the tokenizer was created with.
+///
+/// The tokenizer does not copy the input: identifiers, numbers, comments and string literals are slices of `source`, and only a literal whose text had to be rewritten (a doubled quote or an escape sequence) allocates its own `String`.
 pub struct Tokenizer<'a> {
+    source: &'a str,
     input: Peekable<Chars<'a>>,
+    dialect: &'a dyn Dialect,
+    options: TokenizerOptions,
+    /// Added to every reported offset, see `starting_at`.
+    base: usize,
+    offset: usize,
+    line: usize,
+    column: usize,
+}
+
+/// Settings that change what the tokenizer produces, independently of the dialect. The defaults are what the parser expects.
+/// * `emit_comments` – yield `-- line` and `/* block */` comments as `Token::Comment` instead of skipping them.
+/// * `emit_whitespace` – yield runs of whitespace as `Token::Whitespace` instead of skipping them. Together with `emit_comments`, the text of the tokens (and of any errors) covers the input without gaps, which is what `SyntaxTree` is built from.
+/// * `case_folding` – how unquoted identifiers are normalized, overriding `Dialect::case_folding` when set.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct TokenizerOptions {
+    pub emit_comments: bool,
+    pub emit_whitespace: bool,
+    pub case_folding: Option<CaseFolding>,
 }
 
 impl<'a> Tokenizer<'a> {
-    pub fn new(input: &'a str) -> Self {
+    pub fn new(input: &'a str, dialect: &'a dyn Dialect) -> Self {
+        Self::with_options(input, dialect, TokenizerOptions::default())
+    }
+
+    pub fn with_options(input: &'a str, dialect: &'a dyn Dialect, options: TokenizerOptions) -> Self {
         Self {
+            source: input,
             input: input.chars().peekable(),
+            dialect,
+            options,
+            base: 0,
+            offset: 0,
+            line: 1,
+            column: 1,
+        }
+    }
+
+    /// Reports positions as if the input started at `location` instead of at the beginning of a file, for input that is a piece of a larger text (see `StatementReader`). Must be called before any token has been read.
+    pub fn starting_at(mut self, location: Span) -> Self {
+        self.base = location.start;
+        self.line = location.line;
+        self.column = location.column;
+        self
+    }
+
+    /// Returns an empty span at the current position, which is where the next token starts (or the end of the input once everything has been read).
+    pub fn location(&self) -> Span {
+        Span::new(self.base + self.offset, self.base + self.offset, self.line, self.column)
+    }
+
+    /// Returns the input text a span produced by this tokenizer was read from.
+    pub fn text(&self, span: Span) -> &'a str {
+        &self.source[span.start - self.base..span.end - self.base]
+    }
+
+    pub fn dialect(&self) -> &'a dyn Dialect {
+        self.dialect
+    }
+
+    /// Returns the policy unquoted identifiers are normalized with: `TokenizerOptions::case_folding` if set, otherwise the dialect's.
+    pub fn case_folding(&self) -> CaseFolding {
+        self.options.case_folding.unwrap_or_else(|| self.dialect.case_folding())
+    }
+
+    fn span_from(&self, start: Span) -> Span {
+        Span::new(start.start, self.base + self.offset, start.line, start.column)
+    }
+
+    fn peek(&mut self) -> Option<char> {
+        self.input.peek().copied()
+    }
+
+    fn bump(&mut self) -> Option<char> {
+        let c = self.input.next()?;
+        self.offset += c.len_utf8();
+        if c == '\n' {
+            self.line += 1;
+            self.column = 1;
+        } else {
+            self.column += 1;
         }
+        Some(c)
+    }
+
+    /// Returns the input from byte `start` up to the current position.
+    fn slice_from(&self, start: usize) -> &'a str {
+        &self.source[start..self.offset]
     }
 
     fn skip_whitespace(&mut self) {
-        while let Some(&c) = self.input.peek() {
+        while let Some(c) = self.peek() {
             if !c.is_whitespace() {
                 break;
             }
-            self.input.next();
+            self.bump();
         }
     }
 
-    fn read_number(&mut self, first_digit: char) -> Token {
-        let mut number = first_digit.to_string();
-        
-        while let Some(&c) = self.input.peek() {
-            if !c.is_digit(10) {
+    /// Reads a `--` comment up to (but not including) the end of the line. The first `-` has already been consumed.
+    fn read_line_comment(&mut self) -> Token<'a> {
+        let start = self.offset - 1;
+
+        while let Some(c) = self.peek() {
+            if c == '\n' {
                 break;
             }
-            number.push(c);
-            self.input.next();
+            self.bump();
+        }
+
+        Token::Comment(Cow::Borrowed(self.slice_from(start)))
+    }
+
+    /// Reads a `/* */` comment. Block comments nest, so `/* a /* b */ c */` is a single comment. The first `/` has already been consumed.
+    fn read_block_comment(&mut self) -> Result<Token<'a>, ParseErrorKind> {
+        let start = self.offset - 1;
+        self.bump();
+        let mut depth = 1;
+
+        while let Some(c) = self.bump() {
+            match (c, self.peek()) {
+                ('/', Some('*')) | ('*', Some('/')) => {
+                    self.bump();
+                    depth = if c == '/' { depth + 1 } else { depth - 1 };
+                    if depth == 0 {
+                        return Ok(Token::Comment(Cow::Borrowed(self.slice_from(start))));
+                    }
+                },
+                _ => {},
+            }
         }
 
+        Err(ParseErrorKind::UnterminatedComment)
+    }
+
+    /// Reads a numeric literal. A plain run of digits becomes `Token::Number`; a literal with a fractional part (`9.99`, `1.`, `.5`) or an exponent (`1e6`, `2.5E-3`) becomes `Token::Decimal` holding the text exactly as written, so no precision is lost. The same goes for a whole number too large for `u64`, which is kept as an arbitrary-precision `Token::Decimal` rather than rejected. An `e` that is not followed by exponent digits is left for the next token, as in `1else`.
+    fn read_number(&mut self, first_char: char) -> Token<'a> {
+        let start = self.offset - first_char.len_utf8();
+        let mut is_decimal = first_char == '.';
+
+        self.read_digits();
+        if !is_decimal && self.peek() == Some('.') {
+            is_decimal = true;
+            self.bump();
+            self.read_digits();
+        }
+        if matches!(self.peek(), Some('e' | 'E')) && self.has_exponent_digits() {
+            is_decimal = true;
+            self.bump();
+            if matches!(self.peek(), Some('+' | '-')) {
+                self.bump();
+            }
+            self.read_digits();
+        }
+
+        let number = self.slice_from(start);
         match number.parse::<u64>() {
-            Ok(n) => Token::Number(n),
-            Err(_) => Token::Invalid(first_digit),
+            Ok(n) if !is_decimal => Token::Number(n),
+            _ => Token::Decimal(Cow::Borrowed(number)),
         }
     }
 
-    fn read_identifier_or_keyword(&mut self, first_char: char) -> Token {
-        let mut identifier = first_char.to_string();
-        
-        while let Some(&c) = self.input.peek() {
+    fn read_digits(&mut self) {
+        while let Some(c) = self.peek() {
+            if !c.is_ascii_digit() {
+                break;
+            }
+            self.bump();
+        }
+    }
+
+    /// Looks past the `e` of an exponent (and an optional sign) without consuming anything, to check that at least one digit follows.
+    fn has_exponent_digits(&self) -> bool {
+        let mut lookahead = self.input.clone().skip(1).peekable();
+        lookahead.next_if(|c| matches!(c, '+' | '-'));
+        lookahead.next().is_some_and(|c| c.is_ascii_digit())
+    }
+
+    /// Reads the body of an `X'...'` or `B'...'` literal whose prefix has already been consumed, checking that every character is a valid hex or binary digit. An invalid literal is reported with its full text.
+    fn read_byte_string(&mut self, prefix: char) -> Result<Token<'a>, ParseErrorKind> {
+        self.bump();
+        let Token::String(digits) = self.read_string('\'', false)? else {
+            unreachable!("read_string only returns strings");
+        };
+
+        match prefix.to_ascii_uppercase() {
+            'X' if digits.chars().all(|c| c.is_ascii_hexdigit()) => Ok(Token::HexString(digits)),
+            'B' if digits.chars().all(|c| c == '0' || c == '1') => Ok(Token::BitString(digits)),
+            _ => Err(ParseErrorKind::InvalidBinaryLiteral(format!("{}'{}'", prefix, digits))),
+        }
+    }
+
+    /// Reads a `0x` hexadecimal literal; the `0` has already been consumed. Like `X'...'`, it becomes a `Token::HexString`, so hashes of any length can be written without overflowing an integer.
+    fn read_hex_number(&mut self) -> Result<Token<'a>, ParseErrorKind> {
+        let start = self.offset - 1;
+        self.bump();
+        while let Some(c) = self.peek() {
             if !c.is_alphanumeric() && c != '_' {
                 break;
             }
-            identifier.push(c);
-            self.input.next();
+            self.bump();
         }
 
-        // Convert to uppercase for case-insensitive comparison
-        let upper_identifier = identifier.to_uppercase();
-        
-        match upper_identifier.as_str() {
-            "SELECT" => Token::Keyword(Keyword::Select),
-            "CREATE" => Token::Keyword(Keyword::Create),
-            "TABLE" => Token::Keyword(Keyword::Table),
-            "WHERE" => Token::Keyword(Keyword::Where),
-            "ORDER" => Token::Keyword(Keyword::Order),
-            "BY" => Token::Keyword(Keyword::By),
-            "ASC" => Token::Keyword(Keyword::Asc),
-            "DESC" => Token::Keyword(Keyword::Desc),
-            "FROM" => Token::Keyword(Keyword::From),
-            "AND" => Token::Keyword(Keyword::And),
-            "OR" => Token::Keyword(Keyword::Or),
-            "NOT" => Token::Keyword(Keyword::Not),
-            "TRUE" => Token::Keyword(Keyword::True),
-            "FALSE" => Token::Keyword(Keyword::False),
-            "PRIMARY" => Token::Keyword(Keyword::Primary),
-            "KEY" => Token::Keyword(Keyword::Key),
-            "CHECK" => Token::Keyword(Keyword::Check),
-            "INT" => Token::Keyword(Keyword::Int),
-            "BOOL" => Token::Keyword(Keyword::Bool),
-            "VARCHAR" => Token::Keyword(Keyword::Varchar),
-            "NULL" => Token::Keyword(Keyword::Null),
-            _ => Token::Identifier(identifier),
-        }
-    }
-
-    fn read_string(&mut self, quote_char: char) -> Token {
-        let mut string = String::new();
-        
-        while let Some(c) = self.input.next() {
+        let literal = self.slice_from(start);
+        let digits = &literal[2..];
+        if digits.is_empty() || !digits.chars().all(|c| c.is_ascii_hexdigit()) {
+            return Err(ParseErrorKind::InvalidBinaryLiteral(literal.to_string()));
+        }
+        Ok(Token::HexString(Cow::Borrowed(digits)))
+    }
+
+    /// Reads an unquoted identifier: a Unicode `XID_Start` character or `_`, followed by `XID_Continue` characters, so names such as `ąžuolas` or `テーブル` are accepted. Keywords are matched with ASCII case folding only, so a non-ASCII name can never turn into a keyword (`ſelect` stays an identifier), and the identifier keeps its exact spelling.
+    fn read_identifier_or_keyword(&mut self, first_char: char) -> Token<'a> {
+        let start = self.offset - first_char.len_utf8();
+
+        while let Some(c) = self.peek() {
+            if !is_xid_continue(c) {
+                break;
+            }
+            self.bump();
+        }
+
+        let identifier = self.slice_from(start);
+        match Keyword::lookup(identifier) {
+            Some(keyword) if self.dialect.is_keyword(&keyword) => Token::Keyword(keyword),
+            _ => Token::Identifier(Ident::folded(identifier, self.case_folding())),
+        }
+    }
+
+    /// Reads a string literal whose opening quote has already been consumed. A doubled quote (`'O''Brien'`) stands for one quote character. With `backslash_escapes`, backslash sequences are interpreted as well; an invalid sequence is reported once the whole literal has been read, so the tokenizer can carry on after it.
+    ///
+    /// The literal borrows from the input unless a doubled quote or an escape has to be rewritten; only then is its text copied into a `String`.
+    fn read_string(&mut self, quote_char: char, backslash_escapes: bool) -> Result<Token<'a>, ParseErrorKind> {
+        let source = self.source;
+        let start = self.offset;
+        let mut rewritten: Option<String> = None;
+        let mut invalid_escape = None;
+
+        while let Some(c) = self.bump() {
+            let end = self.offset - c.len_utf8();
             if c == quote_char {
-                return Token::String(string);
+                if self.peek() == Some(quote_char) {
+                    self.bump();
+                    rewritten.get_or_insert_with(|| source[start..end].to_string()).push(c);
+                    continue;
+                }
+                return match invalid_escape {
+                    Some(escape) => Err(ParseErrorKind::InvalidEscape(escape)),
+                    None => Ok(Token::String(rewritten.map_or(Cow::Borrowed(&source[start..end]), Cow::Owned))),
+                };
+            }
+
+            if c == '\\' && backslash_escapes {
+                let string = rewritten.get_or_insert_with(|| source[start..end].to_string());
+                let Some(escaped) = self.bump() else {
+                    break;
+                };
+                match Self::unescape(escaped) {
+                    Some(unescaped) => string.push(unescaped),
+                    None => {
+                        invalid_escape.get_or_insert(format!("\\{}", escaped));
+                    },
+                }
+                continue;
+            }
+
+            if let Some(string) = &mut rewritten {
+                string.push(c);
             }
-            string.push(c);
         }
-        
+
         // If we get here, the string was not properly terminated
-        Token::Invalid(quote_char)
+        Err(ParseErrorKind::UnterminatedString)
     }
-}
 
-impl<'a> Iterator for Tokenizer<'a> {
-    type Item = Token;
+    /// Reads a quoted identifier whose opening quote has already been consumed. The identifier keeps its exact spelling; a doubled closing quote (`"a""b"`, `[a]]b]`) stands for one quote character. Like a string literal, it only allocates if it contains a doubled quote.
+    fn read_quoted_identifier(&mut self, quote: char) -> Result<Token<'a>, ParseErrorKind> {
+        let source = self.source;
+        let close = closing_quote(quote);
+        let start = self.offset;
+        let mut rewritten: Option<String> = None;
 
-    fn next(&mut self) -> Option<Self::Item> {
-        self.skip_whitespace();
+        while let Some(c) = self.bump() {
+            let end = self.offset - c.len_utf8();
+            if c == close {
+                if self.peek() == Some(close) {
+                    self.bump();
+                    rewritten.get_or_insert_with(|| source[start..end].to_string()).push(c);
+                    continue;
+                }
+                let value = rewritten.map_or(Cow::Borrowed(&source[start..end]), Cow::Owned);
+                return Ok(Token::Identifier(Ident::with_quote(quote, value)));
+            }
+            if let Some(value) = &mut rewritten {
+                value.push(c);
+            }
+        }
 
-        let next_char = self.input.next()?;
+        Err(ParseErrorKind::UnterminatedIdentifier)
+    }
 
-        let token = match next_char {
-            '0'..='9' => self.read_number(next_char),
-            'a'..='z' | 'A'..='Z' | '_' => self.read_identifier_or_keyword(next_char),
-            '\'' | '"' => self.read_string(next_char),
+    fn unescape(escaped: char) -> Option<char> {
+        match escaped {
+            'n' => Some('\n'),
+            't' => Some('\t'),
+            'r' => Some('\r'),
+            '0' => Some('\0'),
+            '\\' | '\'' | '"' => Some(escaped),
+            _ => None,
+        }
+    }
+
+    /// Consumes `second` if it comes next and the dialect supports the two character `operator` it completes.
+    fn read_operator(&mut self, second: char, operator: &str) -> bool {
+        if self.peek() == Some(second) && self.dialect.supports_operator(operator) {
+            self.bump();
+            return true;
+        }
+        false
+    }
+
+    fn read_symbol(&mut self, first_char: char) -> Result<Token<'a>, ParseErrorKind> {
+        let token = match first_char {
             '(' => Token::LeftParentheses,
             ')' => Token::RightParentheses,
             '>' => {
-                if let Some(&'=') = self.input.peek() {
-                    self.input.next();
+                if let Some('=') = self.peek() {
+                    self.bump();
                     Token::GreaterThanOrEqual
                 } else {
                     Token::GreaterThan
                 }
             },
             '<' => {
-                if let Some(&'=') = self.input.peek() {
-                    self.input.next();
+                if let Some('=') = self.peek() {
+                    self.bump();
                     Token::LessThanOrEqual
+                } else if self.read_operator('>', "<>") {
+                    Token::NotEqual
                 } else {
                     Token::LessThan
                 }
             },
             '=' => Token::Equal,
             '!' => {
-                if let Some(&'=') = self.input.peek() {
-                    self.input.next();
+                if let Some('=') = self.peek() {
+                    self.bump();
                     Token::NotEqual
                 } else {
-                    Token::Invalid('!')
+                    return Err(ParseErrorKind::InvalidCharacter('!'));
                 }
             },
+            '|' if self.read_operator('|', "||") => Token::DoublePipe,
+            ':' if self.read_operator(':', "::") => Token::DoubleColon,
+            '%' if self.dialect.supports_operator("%") => Token::Percent,
             '*' => Token::Star,
             '/' => Token::Divide,
             '-' => Token::Minus,
             '+' => Token::Plus,
             ',' => Token::Comma,
             ';' => Token::Semicolon,
-            c => Token::Invalid(c),
+            c => return Err(ParseErrorKind::InvalidCharacter(c)),
         };
 
-        Some(token)
+        Ok(token)
+    }
+}
+
+impl<'a> Iterator for Tokenizer<'a> {
+    type Item = Result<(Token<'a>, Span), ParseError>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        loop {
+            if self.options.emit_whitespace && self.peek().is_some_and(char::is_whitespace) {
+                let start = self.location();
+                let offset = self.offset;
+                self.skip_whitespace();
+                return Some(Ok((Token::Whitespace(Cow::Borrowed(self.slice_from(offset))), self.span_from(start))));
+            }
+            self.skip_whitespace();
+
+            let start = self.location();
+            let next_char = self.bump()?;
+
+            let token = match next_char {
+                '0' if matches!(self.peek(), Some('x' | 'X')) => self.read_hex_number(),
+                '0'..='9' => Ok(self.read_number(next_char)),
+                '.' if self.peek().is_some_and(|c| c.is_ascii_digit()) => Ok(self.read_number(next_char)),
+                'e' | 'E' if self.peek() == Some('\'') && self.dialect.supports_escape_strings() => {
+                    self.bump();
+                    self.read_string('\'', true)
+                },
+                'x' | 'X' | 'b' | 'B' if self.peek() == Some('\'') => self.read_byte_string(next_char),
+                c if c == '_' || is_xid_start(c) => Ok(self.read_identifier_or_keyword(c)),
+                c if self.dialect.is_identifier_quote(c) => self.read_quoted_identifier(c),
+                c if self.dialect.is_string_quote(c) => self.read_string(c, self.dialect.supports_backslash_escapes()),
+                '-' if self.peek() == Some('-') => Ok(self.read_line_comment()),
+                '/' if self.peek() == Some('*') => self.read_block_comment(),
+                _ => self.read_symbol(next_char),
+            };
+
+            if let Ok(Token::Comment(_)) = token {
+                if !self.options.emit_comments {
+                    continue;
+                }
+            }
+
+            let span = self.span_from(start);
+            return Some(match token {
+                Ok(token) => Ok((token, span)),
+                Err(kind) => Err(ParseError::new(kind, span)),
+            });
+        }
     }
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::dialect::{GenericDialect, MySqlDialect, PostgresDialect, SqliteDialect};
+
+    fn tokens(input: &str) -> Vec<Token<'_>> {
+        Tokenizer::new(input, &GenericDialect)
+            .map(|result| result.map(|(token, _)| token))
+            .collect::<Result<_, _>>()
+            .unwrap()
+    }
 
     #[test]
     fn test_basic_tokens() {
         let input = "SELECT * FROM users;";
-        let tokenizer = Tokenizer::new(input);
-        let tokens: Vec<Token> = tokenizer.collect();
         
-        assert_eq!(tokens, vec![
+        assert_eq!(tokens(input), vec![
             Token::Keyword(Keyword::Select),
             Token::Star,
             Token::Keyword(Keyword::From),
-            Token::Identifier("users".to_string()),
+            Token::Identifier(Ident::new("users")),
             Token::Semicolon,
         ]);
     }
 
     #[test]
     fn test_string_literals() {
-        let input = "'hello' \"world\"";
-        let tokenizer = Tokenizer::new(input);
-        let tokens: Vec<Token> = tokenizer.collect();
+        let input = "'hello' 'world'";
         
-        assert_eq!(tokens, vec![
-            Token::String("hello".to_string()),
-            Token::String("world".to_string()),
+        assert_eq!(tokens(input), vec![
+            Token::String("hello".into()),
+            Token::String("world".into()),
         ]);
     }
 
     #[test]
     fn test_numbers_and_operators() {
         let input = "42 >= 30";
-        let tokenizer = Tokenizer::new(input);
-        let tokens: Vec<Token> = tokenizer.collect();
         
-        assert_eq!(tokens, vec![
+        assert_eq!(tokens(input), vec![
             Token::Number(42),
             Token::GreaterThanOrEqual,
             Token::Number(30),
         ]);
     }
+
+    #[test]
+    fn test_lexical_errors() {
+        let mut tokenizer = Tokenizer::new("'unterminated", &GenericDialect);
+        let error = tokenizer.next().unwrap().unwrap_err();
+        assert_eq!(error.kind(), &ParseErrorKind::UnterminatedString);
+        assert_eq!(error.span().range(), 0..13);
+
+        let mut tokenizer = Tokenizer::new("id @ 99999999999999999999", &GenericDialect);
+        assert_eq!(tokenizer.next().unwrap().unwrap().0, Token::Identifier(Ident::new("id")));
+        let error = tokenizer.next().unwrap().unwrap_err();
+        assert_eq!(error.kind(), &ParseErrorKind::InvalidCharacter('@'));
+        assert_eq!(error.span().range(), 3..4);
+        assert_eq!(tokenizer.next().unwrap().unwrap().0, Token::Decimal("99999999999999999999".into()));
+        assert!(tokenizer.next().is_none());
+    }
+
+    #[test]
+    fn test_spans() {
+        let input = "SELECT id,\n  name FROM users;";
+        let spans: Vec<Span> = Tokenizer::new(input, &GenericDialect)
+            .map(|result| result.unwrap().1)
+            .collect();
+
+        let positions: Vec<_> = spans.iter().map(|span| (span.range(), span.line, span.column)).collect();
+        assert_eq!(positions, vec![
+            (0..6, 1, 1),
+            (7..9, 1, 8),
+            (9..10, 1, 10),
+            (13..17, 2, 3),
+            (18..22, 2, 8),
+            (23..28, 2, 13),
+            (28..29, 2, 18),
+        ]);
+        assert_eq!(&input[spans[3].range()], "name");
+    }
+
+    #[test]
+    fn test_comments() {
+        let input = "-- users older than 18\nSELECT id /* the /* nested */ key */ FROM users; --done";
+        assert_eq!(tokens(input), vec![
+            Token::Keyword(Keyword::Select),
+            Token::Identifier(Ident::new("id")),
+            Token::Keyword(Keyword::From),
+            Token::Identifier(Ident::new("users")),
+            Token::Semicolon,
+        ]);
+
+        let options = TokenizerOptions { emit_comments: true, ..Default::default() };
+        let comments: Vec<(Token, Span)> = Tokenizer::with_options(input, &GenericDialect, options)
+            .map(Result::unwrap)
+            .filter(|(token, _)| matches!(token, Token::Comment(_)))
+            .collect();
+        assert_eq!(comments.len(), 3);
+        assert_eq!(comments[0].0, Token::Comment("-- users older than 18".into()));
+        assert_eq!(comments[1].0, Token::Comment("/* the /* nested */ key */".into()));
+        assert_eq!(&input[comments[2].1.range()], "--done");
+    }
+
+    #[test]
+    fn test_unterminated_comment() {
+        let mut tokenizer = Tokenizer::new("SELECT 1 /* a /* b */", &GenericDialect);
+        tokenizer.next();
+        tokenizer.next();
+        let error = tokenizer.next().unwrap().unwrap_err();
+        assert_eq!(error.kind(), &ParseErrorKind::UnterminatedComment);
+        assert_eq!(error.span().range(), 9..21);
+
+        assert_eq!(tokens("5 - -3 / 2"), vec![
+            Token::Number(5),
+            Token::Minus,
+            Token::Minus,
+            Token::Number(3),
+            Token::Divide,
+            Token::Number(2),
+        ]);
+    }
+
+    #[test]
+    fn test_escaped_quotes() {
+        assert_eq!(tokens(r#"'O''Brien' "say ""hi""" '' 'a\nb'"#), vec![
+            Token::String("O'Brien".into()),
+            Token::Identifier(Ident::with_quote('"', "say \"hi\"")),
+            Token::String("".into()),
+            Token::String("a\\nb".into()),
+        ]);
+    }
+
+    #[test]
+    fn test_backslash_escapes() {
+        let input = r"'it\'s' 'tab\there' 'back\\slash'";
+        let strings: Vec<Token> = Tokenizer::new(input, &MySqlDialect)
+            .map(|result| result.unwrap().0)
+            .collect();
+        assert_eq!(strings, vec![
+            Token::String("it's".into()),
+            Token::String("tab\there".into()),
+            Token::String("back\\slash".into()),
+        ]);
+
+        assert_eq!(tokens(r"E'line\none' e'\''"), vec![
+            Token::String("line\none".into()),
+            Token::String("'".into()),
+        ]);
+        assert_eq!(tokens("E id"), vec![
+            Token::Identifier(Ident::new("E")),
+            Token::Identifier(Ident::new("id")),
+        ]);
+
+        let mut tokenizer = Tokenizer::new(r"E'bad \q escape' 1", &GenericDialect);
+        let error = tokenizer.next().unwrap().unwrap_err();
+        assert_eq!(error.kind(), &ParseErrorKind::InvalidEscape(r"\q".to_string()));
+        assert_eq!(error.span().range(), 0..16);
+        assert_eq!(tokenizer.next().unwrap().unwrap().0, Token::Number(1));
+    }
+
+    #[test]
+    fn test_quoted_identifiers() {
+        assert_eq!(tokens(r#"SELECT "order", "My ""Table""" FROM users"#), vec![
+            Token::Keyword(Keyword::Select),
+            Token::Identifier(Ident::with_quote('"', "order")),
+            Token::Comma,
+            Token::Identifier(Ident::with_quote('"', "My \"Table\"")),
+            Token::Keyword(Keyword::From),
+            Token::Identifier(Ident::new("users")),
+        ]);
+
+        let tokens: Vec<Token> = Tokenizer::new("`select` [my table] [a]]b]", &SqliteDialect)
+            .map(|result| result.unwrap().0)
+            .collect();
+        assert_eq!(tokens, vec![
+            Token::Identifier(Ident::with_quote('`', "select")),
+            Token::Identifier(Ident::with_quote('[', "my table")),
+            Token::Identifier(Ident::with_quote('[', "a]b")),
+        ]);
+
+        let error = Tokenizer::new("`id`", &GenericDialect).next().unwrap().unwrap_err();
+        assert_eq!(error.kind(), &ParseErrorKind::InvalidCharacter('`'));
+        let error = Tokenizer::new("\"id", &GenericDialect).next().unwrap().unwrap_err();
+        assert_eq!(error.kind(), &ParseErrorKind::UnterminatedIdentifier);
+    }
+
+    #[test]
+    fn test_decimal_literals() {
+        assert_eq!(tokens("9.99 1. .5 1e6 2.5E-3 6e+2"), vec![
+            Token::Decimal("9.99".into()),
+            Token::Decimal("1.".into()),
+            Token::Decimal(".5".into()),
+            Token::Decimal("1e6".into()),
+            Token::Decimal("2.5E-3".into()),
+            Token::Decimal("6e+2".into()),
+        ]);
+
+        assert_eq!(tokens("1else"), vec![Token::Number(1), Token::Identifier(Ident::new("else"))]);
+        assert_eq!(tokens("1.5.5"), vec![Token::Decimal("1.5".into()), Token::Decimal(".5".into())]);
+    }
+
+    #[test]
+    fn test_binary_literals() {
+        assert_eq!(tokens("0xFF X'DEADbeef' x'' B'1010' b'0'"), vec![
+            Token::HexString("FF".into()),
+            Token::HexString("DEADbeef".into()),
+            Token::HexString("".into()),
+            Token::BitString("1010".into()),
+            Token::BitString("0".into()),
+        ]);
+        assert_eq!(tokens("x b"), vec![Token::Identifier(Ident::new("x")), Token::Identifier(Ident::new("b"))]);
+
+        for (input, literal) in [("X'0G'", "X'0G'"), ("b'102'", "b'102'"), ("0xZZ", "0xZZ"), ("0x", "0x")] {
+            let error = Tokenizer::new(input, &GenericDialect).next().unwrap().unwrap_err();
+            assert_eq!(error.kind(), &ParseErrorKind::InvalidBinaryLiteral(literal.to_string()));
+            assert_eq!(error.span().range(), 0..input.len());
+        }
+    }
+
+    #[test]
+    fn test_dialect_operators() {
+        let input = "a <> b || c::INT % 2";
+        assert_eq!(tokens(input), vec![
+            Token::Identifier(Ident::new("a")),
+            Token::NotEqual,
+            Token::Identifier(Ident::new("b")),
+            Token::DoublePipe,
+            Token::Identifier(Ident::new("c")),
+            Token::DoubleColon,
+            Token::Keyword(Keyword::Int),
+            Token::Percent,
+            Token::Number(2),
+        ]);
+
+        let error = Tokenizer::new(input, &SqliteDialect).find_map(Result::err).unwrap();
+        assert_eq!(error.kind(), &ParseErrorKind::InvalidCharacter(':'));
+        assert_eq!(error.span().range(), 11..12);
+    }
+
+    #[test]
+    fn test_dialect_quoting_and_keywords() {
+        let mysql: Vec<Token> = Tokenizer::new(r#""it\'s" `order` bytea"#, &MySqlDialect)
+            .map(|result| result.unwrap().0)
+            .collect();
+        assert_eq!(mysql, vec![
+            Token::String("it's".into()),
+            Token::Identifier(Ident::with_quote('`', "order")),
+            Token::Identifier(Ident::new("bytea")),
+        ]);
+
+        let postgres: Vec<Token> = Tokenizer::new("BLOB BYTEA", &PostgresDialect)
+            .map(|result| result.unwrap().0)
+            .collect();
+        assert_eq!(postgres, vec![Token::Identifier(Ident::folded("BLOB", CaseFolding::Lower)), Token::Keyword(Keyword::Bytea)]);
+
+        let sqlite: Vec<Token> = Tokenizer::new(r"E'\n'", &SqliteDialect)
+            .map(|result| result.unwrap().0)
+            .collect();
+        assert_eq!(sqlite, vec![Token::Identifier(Ident::new("E")), Token::String(r"\n".into())]);
+    }
+
+    #[test]
+    fn test_unicode_identifiers() {
+        assert_eq!(tokens("SELECT ąžuolas, _id2, テーブル FROM äpfel;"), vec![
+            Token::Keyword(Keyword::Select),
+            Token::Identifier(Ident::new("ąžuolas")),
+            Token::Comma,
+            Token::Identifier(Ident::new("_id2")),
+            Token::Comma,
+            Token::Identifier(Ident::new("テーブル")),
+            Token::Keyword(Keyword::From),
+            Token::Identifier(Ident::new("äpfel")),
+            Token::Semicolon,
+        ]);
+
+        // Only ASCII letters fold into keywords: U+017F LATIN SMALL LETTER LONG S uppercases to 'S'.
+        assert_eq!(tokens("ſelect"), vec![Token::Identifier(Ident::new("ſelect"))]);
+
+        let mut tokenizer = Tokenizer::new("ąž ½", &GenericDialect);
+        assert_eq!(tokenizer.next().unwrap().unwrap().1.range(), 0..4);
+        let error = tokenizer.next().unwrap().unwrap_err();
+        assert_eq!(error.kind(), &ParseErrorKind::InvalidCharacter('½'));
+        assert_eq!((error.span().line, error.span().column), (1, 4));
+    }
+
+    #[test]
+    fn test_borrowed_text() {
+        let tokens = tokens("users 'plain' 'O''Brien' \"Name\" 9.99 -- note");
+        let borrowed: Vec<bool> = tokens.iter().map(|token| match token {
+            Token::Identifier(ident) => matches!(ident.value, Cow::Borrowed(_)),
+            Token::String(text) | Token::Decimal(text) | Token::Comment(text) => matches!(text, Cow::Borrowed(_)),
+            other => panic!("unexpected token {:?}", other),
+        }).collect();
+        assert_eq!(borrowed, vec![true, true, false, true, true]);
+        assert_eq!(tokens[2], Token::String("O'Brien".into()));
+
+        let escaped: Vec<Token> = Tokenizer::new(r"'a\tb'", &MySqlDialect).map(|result| result.unwrap().0).collect();
+        assert!(matches!(&escaped[..], [Token::String(Cow::Owned(text))] if text == "a\tb"));
+    }
 }
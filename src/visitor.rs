@@ -0,0 +1,484 @@
+use std::ops::ControlFlow;
+
+use crate::ident::Ident;
+use crate::statement::{Constraint, DBType, Expression, Statement, TableColumn};
+
+/// Walks a parsed tree by reference. Every node type has a `visit_*` method whose default implementation calls the matching `walk_*` function, which visits the node's children in source order. Statements, column definitions, constraints and expressions also get a `pre_visit_*` hook, called before their children, and a `post_visit_*` hook, called after them.
+///
+/// Every method returns `ControlFlow`: returning `ControlFlow::Break(value)` stops the walk at once and hands `value` back to the caller, while the defaults return `ControlFlow::Continue(())`. To skip a node's children, override its `visit_*` method and do not call `walk_*`.
+///
+/// The nodes are borrowed for `'ast`, so a visitor can keep references into the tree:
+///
+/// ```
+/// use std::ops::ControlFlow;
+/// use angunna_gamage_thilina_ranmal::{parse_sql, Ident, Visitor};
+///
+/// struct Names<'ast>(Vec<&'ast str>);
+///
+/// impl<'ast> Visitor<'ast> for Names<'ast> {
+///     type Break = ();
+///
+///     fn visit_ident(&mut self, ident: &'ast Ident<'_>) -> ControlFlow<()> {
+///         self.0.push(&ident.value);
+///         ControlFlow::Continue(())
+///     }
+/// }
+///
+/// let statements = parse_sql("SELECT id, name FROM users WHERE age > 18;").unwrap();
+/// let mut names = Names(Vec::new());
+/// let _ = names.visit_statement(&statements[0]);
+/// assert_eq!(names.0, ["id", "name", "users", "age"]);
+/// ```
+pub trait Visitor<'ast> {
+    /// The value an early exit returns. Use `()` for a visitor that never stops early.
+    type Break;
+
+    fn visit_statement(&mut self, statement: &'ast Statement<'_>) -> ControlFlow<Self::Break> {
+        walk_statement(self, statement)
+    }
+
+    fn pre_visit_statement(&mut self, _statement: &'ast Statement<'_>) -> ControlFlow<Self::Break> {
+        ControlFlow::Continue(())
+    }
+
+    fn post_visit_statement(&mut self, _statement: &'ast Statement<'_>) -> ControlFlow<Self::Break> {
+        ControlFlow::Continue(())
+    }
+
+    fn visit_table_column(&mut self, column: &'ast TableColumn<'_>) -> ControlFlow<Self::Break> {
+        walk_table_column(self, column)
+    }
+
+    fn pre_visit_table_column(&mut self, _column: &'ast TableColumn<'_>) -> ControlFlow<Self::Break> {
+        ControlFlow::Continue(())
+    }
+
+    fn post_visit_table_column(&mut self, _column: &'ast TableColumn<'_>) -> ControlFlow<Self::Break> {
+        ControlFlow::Continue(())
+    }
+
+    fn visit_constraint(&mut self, constraint: &'ast Constraint<'_>) -> ControlFlow<Self::Break> {
+        walk_constraint(self, constraint)
+    }
+
+    fn pre_visit_constraint(&mut self, _constraint: &'ast Constraint<'_>) -> ControlFlow<Self::Break> {
+        ControlFlow::Continue(())
+    }
+
+    fn post_visit_constraint(&mut self, _constraint: &'ast Constraint<'_>) -> ControlFlow<Self::Break> {
+        ControlFlow::Continue(())
+    }
+
+    fn visit_expression(&mut self, expression: &'ast Expression<'_>) -> ControlFlow<Self::Break> {
+        walk_expression(self, expression)
+    }
+
+    fn pre_visit_expression(&mut self, _expression: &'ast Expression<'_>) -> ControlFlow<Self::Break> {
+        ControlFlow::Continue(())
+    }
+
+    fn post_visit_expression(&mut self, _expression: &'ast Expression<'_>) -> ControlFlow<Self::Break> {
+        ControlFlow::Continue(())
+    }
+
+    /// Called for table names, column names and identifiers in expressions.
+    fn visit_ident(&mut self, _ident: &'ast Ident<'_>) -> ControlFlow<Self::Break> {
+        ControlFlow::Continue(())
+    }
+
+    /// Called for column types and the target types of casts.
+    fn visit_data_type(&mut self, _data_type: &'ast DBType) -> ControlFlow<Self::Break> {
+        ControlFlow::Continue(())
+    }
+}
+
+/// Calls `pre_visit_statement`, visits the select list, the table, the `WHERE` condition and the `ORDER BY` items (or the table name and the column definitions) and calls `post_visit_statement`.
+pub fn walk_statement<'ast, V: Visitor<'ast> + ?Sized>(visitor: &mut V, statement: &'ast Statement<'_>) -> ControlFlow<V::Break> {
+    visitor.pre_visit_statement(statement)?;
+    match statement {
+        Statement::Select { columns, from, r#where, orderby, .. } => {
+            for column in columns {
+                visitor.visit_expression(column)?;
+            }
+            visitor.visit_ident(from)?;
+            if let Some(condition) = r#where {
+                visitor.visit_expression(condition)?;
+            }
+            for item in orderby {
+                visitor.visit_expression(item)?;
+            }
+        },
+        Statement::CreateTable { table_name, column_list, .. } => {
+            visitor.visit_ident(table_name)?;
+            for column in column_list {
+                visitor.visit_table_column(column)?;
+            }
+        },
+    }
+    visitor.post_visit_statement(statement)
+}
+
+pub fn walk_table_column<'ast, V: Visitor<'ast> + ?Sized>(visitor: &mut V, column: &'ast TableColumn<'_>) -> ControlFlow<V::Break> {
+    visitor.pre_visit_table_column(column)?;
+    visitor.visit_ident(&column.column_name)?;
+    visitor.visit_data_type(&column.column_type)?;
+    for constraint in &column.constraints {
+        visitor.visit_constraint(constraint)?;
+    }
+    visitor.post_visit_table_column(column)
+}
+
+pub fn walk_constraint<'ast, V: Visitor<'ast> + ?Sized>(visitor: &mut V, constraint: &'ast Constraint<'_>) -> ControlFlow<V::Break> {
+    visitor.pre_visit_constraint(constraint)?;
+    if let Constraint::Check(expression, _) = constraint {
+        visitor.visit_expression(expression)?;
+    }
+    visitor.post_visit_constraint(constraint)
+}
+
+pub fn walk_expression<'ast, V: Visitor<'ast> + ?Sized>(visitor: &mut V, expression: &'ast Expression<'_>) -> ControlFlow<V::Break> {
+    visitor.pre_visit_expression(expression)?;
+    match expression {
+        Expression::BinaryOperation { left_operand, right_operand, .. } => {
+            visitor.visit_expression(left_operand)?;
+            visitor.visit_expression(right_operand)?;
+        },
+        Expression::UnaryOperation { operand, .. } => visitor.visit_expression(operand)?,
+        Expression::Cast { expression, data_type, .. } => {
+            visitor.visit_expression(expression)?;
+            visitor.visit_data_type(data_type)?;
+        },
+        Expression::Identifier(ident, _) => visitor.visit_ident(ident)?,
+        Expression::Number(..)
+        | Expression::Decimal(..)
+        | Expression::HexString(..)
+        | Expression::BitString(..)
+        | Expression::Bool(..)
+        | Expression::String(..) => {},
+    }
+    visitor.post_visit_expression(expression)
+}
+
+/// Walks a parsed tree by mutable reference, in the same order and with the same hooks as `Visitor`. The `walk_*_mut` functions visit the children, so a hook can rewrite a node in place: a `pre_visit_*` hook before its children are visited, a `post_visit_*` hook after they were rewritten.
+///
+/// The trait is generic over the input lifetime `'a`, so a rewrite can put text borrowed from the input into the tree:
+///
+/// ```
+/// use std::ops::ControlFlow;
+/// use angunna_gamage_thilina_ranmal::{parse_sql, Ident, VisitorMut};
+///
+/// struct Rename;
+///
+/// impl<'a> VisitorMut<'a> for Rename {
+///     type Break = ();
+///
+///     fn visit_ident(&mut self, ident: &mut Ident<'a>) -> ControlFlow<()> {
+///         if ident.value == "users" {
+///             *ident = Ident::new("customers");
+///         }
+///         ControlFlow::Continue(())
+///     }
+/// }
+///
+/// let mut statements = parse_sql("SELECT id FROM users;").unwrap();
+/// let _ = Rename.visit_statement(&mut statements[0]);
+/// assert_eq!(statements[0].to_string(), "SELECT id FROM customers;");
+/// ```
+pub trait VisitorMut<'a> {
+    /// The value an early exit returns. Use `()` for a visitor that never stops early.
+    type Break;
+
+    fn visit_statement(&mut self, statement: &mut Statement<'a>) -> ControlFlow<Self::Break> {
+        walk_statement_mut(self, statement)
+    }
+
+    fn pre_visit_statement(&mut self, _statement: &mut Statement<'a>) -> ControlFlow<Self::Break> {
+        ControlFlow::Continue(())
+    }
+
+    fn post_visit_statement(&mut self, _statement: &mut Statement<'a>) -> ControlFlow<Self::Break> {
+        ControlFlow::Continue(())
+    }
+
+    fn visit_table_column(&mut self, column: &mut TableColumn<'a>) -> ControlFlow<Self::Break> {
+        walk_table_column_mut(self, column)
+    }
+
+    fn pre_visit_table_column(&mut self, _column: &mut TableColumn<'a>) -> ControlFlow<Self::Break> {
+        ControlFlow::Continue(())
+    }
+
+    fn post_visit_table_column(&mut self, _column: &mut TableColumn<'a>) -> ControlFlow<Self::Break> {
+        ControlFlow::Continue(())
+    }
+
+    fn visit_constraint(&mut self, constraint: &mut Constraint<'a>) -> ControlFlow<Self::Break> {
+        walk_constraint_mut(self, constraint)
+    }
+
+    fn pre_visit_constraint(&mut self, _constraint: &mut Constraint<'a>) -> ControlFlow<Self::Break> {
+        ControlFlow::Continue(())
+    }
+
+    fn post_visit_constraint(&mut self, _constraint: &mut Constraint<'a>) -> ControlFlow<Self::Break> {
+        ControlFlow::Continue(())
+    }
+
+    fn visit_expression(&mut self, expression: &mut Expression<'a>) -> ControlFlow<Self::Break> {
+        walk_expression_mut(self, expression)
+    }
+
+    fn pre_visit_expression(&mut self, _expression: &mut Expression<'a>) -> ControlFlow<Self::Break> {
+        ControlFlow::Continue(())
+    }
+
+    fn post_visit_expression(&mut self, _expression: &mut Expression<'a>) -> ControlFlow<Self::Break> {
+        ControlFlow::Continue(())
+    }
+
+    /// Called for table names, column names and identifiers in expressions.
+    fn visit_ident(&mut self, _ident: &mut Ident<'a>) -> ControlFlow<Self::Break> {
+        ControlFlow::Continue(())
+    }
+
+    /// Called for column types and the target types of casts.
+    fn visit_data_type(&mut self, _data_type: &mut DBType) -> ControlFlow<Self::Break> {
+        ControlFlow::Continue(())
+    }
+}
+
+pub fn walk_statement_mut<'a, V: VisitorMut<'a> + ?Sized>(visitor: &mut V, statement: &mut Statement<'a>) -> ControlFlow<V::Break> {
+    visitor.pre_visit_statement(statement)?;
+    match statement {
+        Statement::Select { columns, from, r#where, orderby, .. } => {
+            for column in columns {
+                visitor.visit_expression(column)?;
+            }
+            visitor.visit_ident(from)?;
+            if let Some(condition) = r#where {
+                visitor.visit_expression(condition)?;
+            }
+            for item in orderby {
+                visitor.visit_expression(item)?;
+            }
+        },
+        Statement::CreateTable { table_name, column_list, .. } => {
+            visitor.visit_ident(table_name)?;
+            for column in column_list {
+                visitor.visit_table_column(column)?;
+            }
+        },
+    }
+    visitor.post_visit_statement(statement)
+}
+
+pub fn walk_table_column_mut<'a, V: VisitorMut<'a> + ?Sized>(visitor: &mut V, column: &mut TableColumn<'a>) -> ControlFlow<V::Break> {
+    visitor.pre_visit_table_column(column)?;
+    visitor.visit_ident(&mut column.column_name)?;
+    visitor.visit_data_type(&mut column.column_type)?;
+    for constraint in &mut column.constraints {
+        visitor.visit_constraint(constraint)?;
+    }
+    visitor.post_visit_table_column(column)
+}
+
+pub fn walk_constraint_mut<'a, V: VisitorMut<'a> + ?Sized>(visitor: &mut V, constraint: &mut Constraint<'a>) -> ControlFlow<V::Break> {
+    visitor.pre_visit_constraint(constraint)?;
+    if let Constraint::Check(expression, _) = constraint {
+        visitor.visit_expression(expression)?;
+    }
+    visitor.post_visit_constraint(constraint)
+}
+
+pub fn walk_expression_mut<'a, V: VisitorMut<'a> + ?Sized>(visitor: &mut V, expression: &mut Expression<'a>) -> ControlFlow<V::Break> {
+    visitor.pre_visit_expression(expression)?;
+    match expression {
+        Expression::BinaryOperation { left_operand, right_operand, .. } => {
+            visitor.visit_expression(left_operand)?;
+            visitor.visit_expression(right_operand)?;
+        },
+        Expression::UnaryOperation { operand, .. } => visitor.visit_expression(operand)?,
+        Expression::Cast { expression, data_type, .. } => {
+            visitor.visit_expression(expression)?;
+            visitor.visit_data_type(data_type)?;
+        },
+        Expression::Identifier(ident, _) => visitor.visit_ident(ident)?,
+        Expression::Number(..)
+        | Expression::Decimal(..)
+        | Expression::HexString(..)
+        | Expression::BitString(..)
+        | Expression::Bool(..)
+        | Expression::String(..) => {},
+    }
+    visitor.post_visit_expression(expression)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::parse_sql;
+    use crate::span::Span;
+    use crate::statement::BinaryOperator;
+
+    /// Records every hook call, to check the order of the walk.
+    #[derive(Default)]
+    struct Trace(Vec<String>);
+
+    impl<'ast> Visitor<'ast> for Trace {
+        type Break = ();
+
+        fn pre_visit_statement(&mut self, _statement: &'ast Statement<'_>) -> ControlFlow<()> {
+            self.0.push("statement".into());
+            ControlFlow::Continue(())
+        }
+
+        fn post_visit_statement(&mut self, _statement: &'ast Statement<'_>) -> ControlFlow<()> {
+            self.0.push("/statement".into());
+            ControlFlow::Continue(())
+        }
+
+        fn pre_visit_table_column(&mut self, column: &'ast TableColumn<'_>) -> ControlFlow<()> {
+            self.0.push(format!("column {}", column.column_name));
+            ControlFlow::Continue(())
+        }
+
+        fn pre_visit_constraint(&mut self, constraint: &'ast Constraint<'_>) -> ControlFlow<()> {
+            self.0.push(format!("constraint {}", constraint));
+            ControlFlow::Continue(())
+        }
+
+        fn pre_visit_expression(&mut self, expression: &'ast Expression<'_>) -> ControlFlow<()> {
+            self.0.push(format!("expression {}", expression));
+            ControlFlow::Continue(())
+        }
+
+        fn post_visit_expression(&mut self, expression: &'ast Expression<'_>) -> ControlFlow<()> {
+            self.0.push(format!("/expression {}", expression));
+            ControlFlow::Continue(())
+        }
+
+        fn visit_ident(&mut self, ident: &'ast Ident<'_>) -> ControlFlow<()> {
+            self.0.push(format!("ident {}", ident));
+            ControlFlow::Continue(())
+        }
+
+        fn visit_data_type(&mut self, data_type: &'ast DBType) -> ControlFlow<()> {
+            self.0.push(format!("type {}", data_type));
+            ControlFlow::Continue(())
+        }
+    }
+
+    fn trace(sql: &str) -> Vec<String> {
+        let statements = parse_sql(sql).unwrap();
+        let mut trace = Trace::default();
+        assert_eq!(trace.visit_statement(&statements[0]), ControlFlow::Continue(()));
+        trace.0
+    }
+
+    #[test]
+    fn test_walk_order() {
+        assert_eq!(
+            trace("SELECT -a FROM t WHERE b::INT > 1 ORDER BY c;"),
+            [
+                "statement",
+                "expression -a",
+                "expression a",
+                "ident a",
+                "/expression a",
+                "/expression -a",
+                "ident t",
+                "expression b::INT > 1",
+                "expression b::INT",
+                "expression b",
+                "ident b",
+                "/expression b",
+                "type INT",
+                "/expression b::INT",
+                "expression 1",
+                "/expression 1",
+                "/expression b::INT > 1",
+                "expression c",
+                "ident c",
+                "/expression c",
+                "/statement",
+            ]
+        );
+        assert_eq!(
+            trace("CREATE TABLE t (id INT PRIMARY KEY CHECK (id > 0));"),
+            [
+                "statement",
+                "ident t",
+                "column id",
+                "ident id",
+                "type INT",
+                "constraint PRIMARY KEY",
+                "constraint CHECK (id > 0)",
+                "expression id > 0",
+                "expression id",
+                "ident id",
+                "/expression id",
+                "expression 0",
+                "/expression 0",
+                "/expression id > 0",
+                "/statement",
+            ]
+        );
+    }
+
+    /// Stops at the first string literal and returns its span.
+    struct FirstString;
+
+    impl<'ast> Visitor<'ast> for FirstString {
+        type Break = Span;
+
+        fn pre_visit_expression(&mut self, expression: &'ast Expression<'_>) -> ControlFlow<Span> {
+            match expression {
+                Expression::String(_, span) => ControlFlow::Break(*span),
+                _ => ControlFlow::Continue(()),
+            }
+        }
+    }
+
+    #[test]
+    fn test_early_exit() {
+        let statements = parse_sql("SELECT a FROM t WHERE b = 'x' OR c = 'y';").unwrap();
+        assert_eq!(FirstString.visit_statement(&statements[0]), ControlFlow::Break(Span::new(26, 29, 1, 27)));
+
+        let statements = parse_sql("SELECT a FROM t WHERE b = 1;").unwrap();
+        assert_eq!(FirstString.visit_statement(&statements[0]), ControlFlow::Continue(()));
+    }
+
+    /// Replaces every `x AND TRUE` with `x`, after the children have been simplified.
+    struct Simplify;
+
+    impl<'a> VisitorMut<'a> for Simplify {
+        type Break = ();
+
+        fn post_visit_expression(&mut self, expression: &mut Expression<'a>) -> ControlFlow<()> {
+            if let Expression::BinaryOperation { left_operand, operator: BinaryOperator::And, right_operand, .. } = expression {
+                if matches!(**right_operand, Expression::Bool(true, _)) {
+                    let left = std::mem::replace(&mut **left_operand, Expression::Bool(true, Span::default()));
+                    *expression = left;
+                }
+            }
+            ControlFlow::Continue(())
+        }
+
+        fn visit_data_type(&mut self, data_type: &mut DBType) -> ControlFlow<()> {
+            if *data_type == DBType::Bool {
+                *data_type = DBType::Int;
+            }
+            ControlFlow::Continue(())
+        }
+    }
+
+    #[test]
+    fn test_visitor_mut() {
+        let mut statements = parse_sql("SELECT a FROM t WHERE (b AND TRUE) AND TRUE OR c::BOOL; CREATE TABLE t (a BOOL CHECK (a AND TRUE));").unwrap();
+        for statement in &mut statements {
+            assert_eq!(Simplify.visit_statement(statement), ControlFlow::Continue(()));
+        }
+        assert_eq!(statements[0].to_string(), "SELECT a FROM t WHERE b OR c::INT;");
+        assert_eq!(statements[1].to_string(), "CREATE TABLE t (a INT CHECK (a));");
+    }
+}